@@ -0,0 +1,138 @@
+use emerge::graph::Graph;
+use emerge::layout::Layout;
+use emerge::physics::PhysicsConfig;
+use hecs::World;
+use std::collections::HashMap;
+use std::time::Instant;
+
+const NODE_COUNT: usize = 2_000;
+const STEPS: u32 = 20;
+
+fn build_graph() -> Graph {
+    let mut graph = Graph::new();
+
+    let node_ids: Vec<_> = (0..NODE_COUNT)
+        .map(|node| graph.add_node(&node.to_string()))
+        .collect();
+
+    // 6n + 1 is odd and so never a multiple of the even node count, meaning (7n + 1) % NODE_COUNT
+    // never lands back on n and the undirected edges never loop.
+    for node in 1..NODE_COUNT {
+        graph.add_directed_edge(node_ids[node / 2], node_ids[node]);
+        graph.add_undirected_edge(node_ids[node], node_ids[(node * 7 + 1) % NODE_COUNT]);
+    }
+
+    graph
+}
+
+// The edges of the benchmark graph as pairs of node indices, matching build_graph.
+fn edge_indices() -> Vec<(usize, usize)> {
+    (1..NODE_COUNT)
+        .flat_map(|node| [(node / 2, node), (node, (node * 7 + 1) % NODE_COUNT)])
+        .collect()
+}
+
+fn bench(name: &str, config: PhysicsConfig) {
+    let mut layout = Layout::new(build_graph()).with_config(config);
+
+    let start = Instant::now();
+    layout.run(STEPS as usize);
+    let elapsed = start.elapsed();
+
+    println!(
+        "{}, {} nodes: {:?} per step ({} steps in {:?})",
+        name,
+        NODE_COUNT,
+        elapsed / STEPS,
+        STEPS,
+        elapsed
+    );
+}
+
+struct Force {
+    x: f32,
+}
+
+// Applies one force to each end of every edge and one to every node, the pattern of a physics
+// step, without any of the force calculations. This isolates how a node's force is found.
+fn bench_force_lookup(name: &str, apply_forces: impl Fn(&mut World, &[(usize, usize)])) {
+    let mut world = World::new();
+    world.spawn_batch((0..NODE_COUNT).map(|node| (node, Force { x: 0.0 })));
+    let edges = edge_indices();
+
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        apply_forces(&mut world, &edges);
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{}, {} nodes: {:?} per step ({} steps in {:?})",
+        name,
+        NODE_COUNT,
+        elapsed / STEPS,
+        STEPS,
+        elapsed
+    );
+}
+
+// How forces were applied before the dense buffer: searching the world for the node every time.
+fn apply_forces_by_searching(world: &mut World, edges: &[(usize, usize)]) {
+    let apply = |id: usize| {
+        let mut query = world.query::<(&mut Force, &usize)>();
+        let (_, (force, _)) = query
+            .iter()
+            .find(|(_, (_, &node_id))| node_id == id)
+            .unwrap();
+        force.x += 1.0;
+    };
+
+    for &(source, destination) in edges {
+        apply(source);
+        apply(destination);
+    }
+    for node in 0..NODE_COUNT {
+        apply(node);
+    }
+}
+
+// How forces are applied now: indexing the nodes once, accumulating densely and writing back once.
+fn apply_forces_through_a_buffer(world: &mut World, edges: &[(usize, usize)]) {
+    let mut entities = Vec::new();
+    let mut index_by_node_id = HashMap::new();
+    for (entity, &node_id) in world.query_mut::<&usize>() {
+        index_by_node_id.insert(node_id, entities.len());
+        entities.push(entity);
+    }
+
+    let mut forces = vec![0.0; entities.len()];
+    for &(source, destination) in edges {
+        forces[index_by_node_id[&source]] += 1.0;
+        forces[index_by_node_id[&destination]] += 1.0;
+    }
+    for node in 0..NODE_COUNT {
+        forces[index_by_node_id[&node]] += 1.0;
+    }
+
+    for (entity, force) in entities.into_iter().zip(forces) {
+        world.get::<&mut Force>(entity).unwrap().x += force;
+    }
+}
+
+fn main() {
+    // A theta of zero compares every pair of nodes, the naive O(n²) repulsion, as the baseline.
+    bench(
+        "naive repulsion",
+        PhysicsConfig {
+            theta: 0.0,
+            ..PhysicsConfig::default()
+        },
+    );
+    bench("Barnes-Hut repulsion", PhysicsConfig::default());
+
+    bench_force_lookup("forces by entity search", apply_forces_by_searching);
+    bench_force_lookup(
+        "forces through a dense buffer",
+        apply_forces_through_a_buffer,
+    );
+}
@@ -7,14 +7,8 @@ use std::path::Path;
 
 #[derive(Debug, Deserialize)]
 struct Country {
-    #[serde(rename = "Country code")]
-    country_code: String,
     #[serde(rename = "Name")]
     name: String,
-    #[serde(rename = "Official name")]
-    official_name: String,
-    #[serde(rename = "Citizen names")]
-    citizens_name: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -61,8 +55,9 @@ pub fn country_chain_finder() -> Graph {
 
     for country_i in starting_candidates.iter() {
         for country_j in starting_candidates.iter() {
-            if country_i.first_letter.to_ascii_lowercase()
-                == country_j.last_letter.to_ascii_lowercase()
+            if country_i
+                .first_letter
+                .eq_ignore_ascii_case(&country_j.last_letter)
             {
                 let country_1_name = &country_i.name;
                 let country_2_name = &country_j.name;
@@ -0,0 +1,16 @@
+use emerge::dot;
+use emerge::graph::{default_window_conf, render_graph};
+use std::fs;
+
+#[macroquad::main(default_window_conf)]
+async fn main() {
+    let path = "examples/resources/services.dot";
+    let input = fs::read_to_string(path).expect("The example DOT file could not be read");
+
+    let graph = match dot::parse(&input) {
+        Ok(graph) => graph,
+        Err(error) => panic!("{}:{}", path, error),
+    };
+
+    render_graph(graph).await;
+}
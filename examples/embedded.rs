@@ -0,0 +1,83 @@
+use emerge::graph::{default_window_conf, Graph};
+use emerge::view::GraphView;
+use macroquad::prelude::*;
+
+const PANEL_WIDTH: f32 = 260.0;
+
+fn build_graph() -> Graph {
+    let mut graph = Graph::new();
+
+    for label in [
+        "Albania", "Cambodia", "Cameroon", "Nigeria", "Ecuador", "Romania",
+    ] {
+        graph.add_node(label);
+    }
+
+    graph.add_directed_edge("Cambodia", "Albania");
+    graph.add_directed_edge("Cameroon", "Nigeria");
+    graph.add_directed_edge("Nigeria", "Albania");
+    graph.add_directed_edge("Ecuador", "Romania");
+    graph.add_directed_edge("Romania", "Albania");
+
+    graph
+}
+
+#[macroquad::main(default_window_conf)]
+async fn main() {
+    let mut graph_view = GraphView::new(build_graph());
+    let mut added_nodes = 0;
+
+    loop {
+        clear_background(BLACK);
+
+        if is_key_pressed(KeyCode::G) {
+            let view = graph_view.view_mut();
+            view.follow_centroid = !view.follow_centroid;
+        }
+
+        // Adds a node joined to every selected node.
+        if is_key_pressed(KeyCode::N) {
+            let selected_nodes = graph_view.selected_nodes();
+
+            added_nodes += 1;
+            graph_view.edit(|graph| {
+                let new_node = graph.add_node(&format!("New {}", added_nodes));
+
+                for node_id in selected_nodes {
+                    graph.add_directed_edge(new_node, node_id);
+                }
+            });
+        }
+
+        graph_view.handle_input();
+        graph_view.update(get_frame_time());
+        graph_view.draw(Rect::new(
+            PANEL_WIDTH,
+            0.0,
+            screen_width() - PANEL_WIDTH,
+            screen_height(),
+        ));
+
+        let follow_centroid = graph_view.view_mut().follow_centroid;
+        let status = if graph_view.is_settled() {
+            "Settled"
+        } else {
+            "Settling"
+        };
+
+        let follow_label = format!("G: follow centre ({})", follow_centroid);
+
+        draw_text(status, 20.0, 40.0, 30.0, WHITE);
+        draw_text(&follow_label, 20.0, 70.0, 20.0, GRAY);
+        draw_text("N: add a node to the selection", 20.0, 95.0, 20.0, GRAY);
+        draw_text("Selected:", 20.0, 135.0, 24.0, WHITE);
+
+        for (index, node_id) in graph_view.selected_nodes().into_iter().enumerate() {
+            let label = graph_view.graph().label(node_id).unwrap_or_default();
+
+            draw_text(label, 20.0, 165.0 + 24.0 * index as f32, 20.0, SKYBLUE);
+        }
+
+        next_frame().await
+    }
+}
@@ -22,13 +22,14 @@ fn compute_factor_tree(visited_nodes: &mut Vec<i32>, value: i32, graph: &mut Gra
             let second_factor = value / number_to_check_as_factor;
 
             visited_nodes.push(value);
-            graph.add_node(&first_factor.to_string());
-            graph.add_directed_edge(&value.to_string(), &first_factor.to_string());
+            let value_node = graph.add_node(&value.to_string());
+            let first_factor_node = graph.add_node(&first_factor.to_string());
+            graph.add_directed_edge(value_node, first_factor_node);
             compute_factor_tree(visited_nodes, first_factor, graph);
 
             if first_factor != second_factor {
-                graph.add_node(&second_factor.to_string());
-                graph.add_directed_edge(&value.to_string(), &second_factor.to_string());
+                let second_factor_node = graph.add_node(&second_factor.to_string());
+                graph.add_directed_edge(value_node, second_factor_node);
 
                 compute_factor_tree(visited_nodes, second_factor, graph);
             }
@@ -0,0 +1,48 @@
+use emerge::graph::{default_window_conf, render_graph_with_updates, Graph, GraphUpdate};
+use emerge::physics::PhysicsConfig;
+use random::Rng;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+// Grows a random tree from a worker thread, one node every few hundred milliseconds.
+fn produce_updates(sender: mpsc::Sender<GraphUpdate>) {
+    let mut rng = random::rng();
+
+    for index in 1..200 {
+        let label = index.to_string();
+        let parent = rng.random_range(0..index).to_string();
+
+        let updates = [
+            GraphUpdate::AddNode {
+                label: label.clone(),
+            },
+            GraphUpdate::AddEdge {
+                from: parent,
+                to: label,
+                is_directed: true,
+                weight: 1.0,
+            },
+        ];
+
+        for update in updates {
+            if sender.send(update).is_err() {
+                return;
+            }
+        }
+
+        thread::sleep(Duration::from_millis(300));
+    }
+}
+
+#[macroquad::main(default_window_conf)]
+async fn main() {
+    let (sender, receiver) = mpsc::channel();
+    let mut graph = Graph::new();
+
+    graph.add_node("0");
+
+    thread::spawn(move || produce_updates(sender));
+
+    render_graph_with_updates(graph, PhysicsConfig::default(), receiver).await;
+}
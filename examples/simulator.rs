@@ -22,14 +22,14 @@ impl Simulator {
 
         let mut graph = Graph::new();
 
-        for node_id in 0..self.number_of_nodes {
-            graph.add_node(&format!("{}", node_id));
-        }
+        let node_ids: Vec<_> = (0..self.number_of_nodes)
+            .map(|node_id| graph.add_node(&format!("{}", node_id)))
+            .collect();
 
         for _ in 0..self.number_of_edges {
             graph.add_undirected_edge(
-                &format!("{}", rng.random_range(0..self.number_of_nodes)),
-                &format!("{}", rng.random_range(0..self.number_of_nodes)),
+                node_ids[rng.random_range(0..self.number_of_nodes)],
+                node_ids[rng.random_range(0..self.number_of_nodes)],
             );
         }
 
@@ -0,0 +1,1231 @@
+use crate::graph::{Graph, NodeId};
+use crate::style::{ArrowHead, EdgeStyle, LinePattern, NodeShape, NodeStyle};
+use macroquad::color::Color;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+// Outline width given to nodes that have a `color` but no `penwidth`, as they are drawn without an
+// outline by default.
+const DEFAULT_STROKE_WIDTH: f32 = 2.0;
+// How deeply subgraphs may be nested, as each level is parsed by recursing.
+const MAX_SUBGRAPH_DEPTH: usize = 100;
+// Node attributes turned into styles. Any other node attribute is kept as metadata.
+const STYLE_ATTRIBUTES: [&str; 8] = [
+    "label",
+    "shape",
+    "style",
+    "color",
+    "fillcolor",
+    "fontcolor",
+    "fontsize",
+    "penwidth",
+];
+
+/// A problem found while reading a DOT file, at a line and column both counted from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for DotError {}
+
+/// Reads a graph written in the Graphviz DOT language.
+///
+/// Nodes are labelled with their `label` attribute, or their id if they have none, and `\N` in a
+/// label is replaced with the node's id. Their `shape`, `style`, `color`, `fillcolor`, `fontcolor`,
+/// `fontsize` and `penwidth` become their [`NodeStyle`], and any other attributes are kept as
+/// metadata shown in their tooltip. Edges take their `label` and `weight`, a weight of 0 counting
+/// as the default of 1, and their `color`, `penwidth`, `style`, `arrowhead` and `dir` become their
+/// [`EdgeStyle`]. Nodes inside a subgraph whose name starts with `cluster` are put in a cluster
+/// named after the subgraph's `label`, or its name if it has none, and nodes in nested clusters
+/// only belong to the innermost one. Colours that are not recognised are left at their default, as
+/// are attributes not listed here.
+pub fn parse(input: &str) -> Result<Graph, DotError> {
+    let tokens = Lexer::new(input).tokenize()?;
+    let document = Parser::new(tokens).parse_document()?;
+
+    build_graph(&document)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    // Identifiers, numerals and strings. Only unquoted ids can be keywords.
+    Id { text: String, is_quoted: bool },
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Equals,
+    Semicolon,
+    Comma,
+    Colon,
+    Plus,
+    DirectedEdge,
+    UndirectedEdge,
+    End,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Id { text, .. } => write!(f, "\"{}\"", text),
+            TokenKind::OpenBrace => write!(f, "`{{`"),
+            TokenKind::CloseBrace => write!(f, "`}}`"),
+            TokenKind::OpenBracket => write!(f, "`[`"),
+            TokenKind::CloseBracket => write!(f, "`]`"),
+            TokenKind::Equals => write!(f, "`=`"),
+            TokenKind::Semicolon => write!(f, "`;`"),
+            TokenKind::Comma => write!(f, "`,`"),
+            TokenKind::Colon => write!(f, "`:`"),
+            TokenKind::Plus => write!(f, "`+`"),
+            TokenKind::DirectedEdge => write!(f, "`->`"),
+            TokenKind::UndirectedEdge => write!(f, "`--`"),
+            TokenKind::End => write!(f, "the end of the file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+}
+
+impl Token {
+    fn error(&self, message: impl Into<String>) -> DotError {
+        DotError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match &self.kind {
+            TokenKind::Id {
+                text,
+                is_quoted: false,
+            } => text.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            index: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+
+        self.index += 1;
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn error(&self, line: usize, column: usize, message: impl Into<String>) -> DotError {
+        DotError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, DotError> {
+        let mut tokens = Vec::new();
+
+        loop {
+            self.skip_whitespace_and_comments()?;
+
+            let (line, column) = (self.line, self.column);
+            let Some(c) = self.peek(0) else {
+                tokens.push(Token {
+                    kind: TokenKind::End,
+                    line,
+                    column,
+                });
+
+                return Ok(tokens);
+            };
+
+            let kind = match c {
+                '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' | '+' => {
+                    self.bump();
+
+                    match c {
+                        '{' => TokenKind::OpenBrace,
+                        '}' => TokenKind::CloseBrace,
+                        '[' => TokenKind::OpenBracket,
+                        ']' => TokenKind::CloseBracket,
+                        '=' => TokenKind::Equals,
+                        ';' => TokenKind::Semicolon,
+                        ',' => TokenKind::Comma,
+                        ':' => TokenKind::Colon,
+                        _ => TokenKind::Plus,
+                    }
+                }
+                '-' if self.peek(1) == Some('>') => {
+                    self.bump();
+                    self.bump();
+                    TokenKind::DirectedEdge
+                }
+                '-' if self.peek(1) == Some('-') => {
+                    self.bump();
+                    self.bump();
+                    TokenKind::UndirectedEdge
+                }
+                '"' => self.quoted_string(line, column)?,
+                '<' => self.html_string(line, column)?,
+                c if c == '-' || c == '.' || c.is_ascii_digit() => self.numeral(line, column)?,
+                c if c == '_' || c.is_alphabetic() || !c.is_ascii() => {
+                    let mut text = String::new();
+
+                    while let Some(c) = self
+                        .peek(0)
+                        .filter(|&c| c == '_' || c.is_alphanumeric() || !c.is_ascii())
+                    {
+                        text.push(c);
+                        self.bump();
+                    }
+
+                    TokenKind::Id {
+                        text,
+                        is_quoted: false,
+                    }
+                }
+                c => return Err(self.error(line, column, format!("unexpected character '{}'", c))),
+            };
+
+            tokens.push(Token { kind, line, column });
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) -> Result<(), DotError> {
+        loop {
+            match (self.peek(0), self.peek(1)) {
+                (Some(c), _) if c.is_whitespace() => {
+                    self.bump();
+                }
+                (Some('/'), Some('/')) => self.skip_line(),
+                // Lines starting with `#` are output from the C preprocessor and are ignored.
+                (Some('#'), _) if self.column == 1 => self.skip_line(),
+                (Some('/'), Some('*')) => {
+                    let (line, column) = (self.line, self.column);
+
+                    self.bump();
+                    self.bump();
+
+                    while !(self.peek(0) == Some('*') && self.peek(1) == Some('/')) {
+                        if self.bump().is_none() {
+                            return Err(self.error(line, column, "unterminated comment"));
+                        }
+                    }
+
+                    self.bump();
+                    self.bump();
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while self.peek(0).is_some_and(|c| c != '\n') {
+            self.bump();
+        }
+    }
+
+    fn quoted_string(&mut self, line: usize, column: usize) -> Result<TokenKind, DotError> {
+        let mut text = String::new();
+
+        self.bump();
+
+        loop {
+            match self.bump() {
+                None => return Err(self.error(line, column, "unterminated string")),
+                Some('"') => break,
+                Some('\\') => match self.bump() {
+                    Some('"') => text.push('"'),
+                    // A backslash before a line break continues the string on the next line.
+                    Some('\n') => {}
+                    Some(c) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => return Err(self.error(line, column, "unterminated string")),
+                },
+                Some(c) => text.push(c),
+            }
+        }
+
+        Ok(TokenKind::Id {
+            text,
+            is_quoted: true,
+        })
+    }
+
+    fn html_string(&mut self, line: usize, column: usize) -> Result<TokenKind, DotError> {
+        let mut text = String::new();
+        let mut depth = 0;
+
+        self.bump();
+
+        loop {
+            match self.bump() {
+                None => return Err(self.error(line, column, "unterminated HTML string")),
+                Some('>') if depth == 0 => break,
+                Some(c) => {
+                    match c {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+
+                    text.push(c);
+                }
+            }
+        }
+
+        Ok(TokenKind::Id {
+            text,
+            is_quoted: true,
+        })
+    }
+
+    fn numeral(&mut self, line: usize, column: usize) -> Result<TokenKind, DotError> {
+        let mut text = String::new();
+
+        if self.peek(0) == Some('-') {
+            text.push('-');
+            self.bump();
+        }
+
+        while let Some(c) = self.peek(0).filter(|&c| c == '.' || c.is_ascii_digit()) {
+            text.push(c);
+            self.bump();
+        }
+
+        let digits = text.trim_start_matches('-');
+
+        if digits.is_empty() || digits == "." || digits.matches('.').count() > 1 {
+            return Err(self.error(line, column, format!("invalid number \"{}\"", text)));
+        }
+
+        Ok(TokenKind::Id {
+            text,
+            is_quoted: false,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Attribute {
+    key: String,
+    value: String,
+    line: usize,
+    column: usize,
+}
+
+impl Attribute {
+    fn error(&self, message: impl Into<String>) -> DotError {
+        DotError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn number(&self) -> Result<f32, DotError> {
+        self.value
+            .trim()
+            .parse()
+            .ok()
+            .filter(|number: &f32| number.is_finite())
+            .ok_or_else(|| {
+                self.error(format!(
+                    "`{}` must be a number, got \"{}\"",
+                    self.key, self.value
+                ))
+            })
+    }
+}
+
+#[derive(Debug)]
+struct DotNode {
+    id: String,
+    attributes: Vec<Attribute>,
+    // The name of the innermost cluster the node was first seen in.
+    cluster: Option<String>,
+}
+
+#[derive(Debug)]
+struct DotEdge {
+    from: String,
+    to: String,
+    attributes: Vec<Attribute>,
+    // Where the edge operator is, for errors about the edge as a whole.
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Default)]
+struct Document {
+    is_directed: bool,
+    is_strict: bool,
+    nodes: Vec<DotNode>,
+    node_indices: HashMap<String, usize>,
+    edges: Vec<DotEdge>,
+    cluster_labels: HashMap<String, String>,
+}
+
+// The defaults set by `node [...]` and `edge [...]` statements, which only last until the end of
+// the subgraph they are in.
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    node_attributes: Vec<Attribute>,
+    edge_attributes: Vec<Attribute>,
+    cluster: Option<String>,
+    // Whether the current subgraph is the cluster itself, as subgraphs nested inside a cluster do
+    // not rename it with their own `label`.
+    is_cluster_subgraph: bool,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    document: Document,
+    // How many subgraphs the parser is currently inside.
+    depth: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            position: 0,
+            document: Document::default(),
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+
+        // The last token is always the end of the file, which is never moved past.
+        if self.position < self.tokens.len() - 1 {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        let is_match = &self.peek().kind == kind;
+
+        if is_match {
+            self.advance();
+        }
+
+        is_match
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, DotError> {
+        if self.peek().kind == kind {
+            Ok(self.advance())
+        } else {
+            Err(self.unexpected(&kind.to_string()))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> DotError {
+        let token = self.peek();
+
+        token.error(format!("expected {} but found {}", expected, token.kind))
+    }
+
+    fn parse_document(mut self) -> Result<Document, DotError> {
+        if self.peek().is_keyword("strict") {
+            self.advance();
+            self.document.is_strict = true;
+        }
+
+        if self.peek().is_keyword("digraph") {
+            self.document.is_directed = true;
+        } else if !self.peek().is_keyword("graph") {
+            return Err(self.unexpected("`graph` or `digraph`"));
+        }
+
+        self.advance();
+
+        if matches!(self.peek().kind, TokenKind::Id { .. }) {
+            self.parse_id()?;
+        }
+
+        self.expect(TokenKind::OpenBrace)?;
+        self.parse_statements(&mut Scope::default())?;
+        self.expect(TokenKind::CloseBrace)?;
+        self.expect(TokenKind::End)?;
+
+        Ok(self.document)
+    }
+
+    // Parses statements up to the closing brace, returning the ids of every node they mention.
+    fn parse_statements(&mut self, scope: &mut Scope) -> Result<Vec<String>, DotError> {
+        let mut node_ids = Vec::new();
+
+        while !matches!(self.peek().kind, TokenKind::CloseBrace | TokenKind::End) {
+            node_ids.extend(self.parse_statement(scope)?);
+            self.eat(&TokenKind::Semicolon);
+        }
+
+        Ok(node_ids)
+    }
+
+    fn parse_statement(&mut self, scope: &mut Scope) -> Result<Vec<String>, DotError> {
+        let token = self.peek().clone();
+
+        for keyword in ["graph", "node", "edge"] {
+            if token.is_keyword(keyword) {
+                self.advance();
+
+                let attributes = self.parse_attribute_lists()?;
+
+                if attributes.is_empty() {
+                    return Err(self.unexpected("`[`"));
+                }
+
+                match keyword {
+                    "graph" => self.set_graph_attributes(scope, &attributes),
+                    "node" => scope.node_attributes.extend(attributes),
+                    _ => scope.edge_attributes.extend(attributes),
+                }
+
+                return Ok(Vec::new());
+            }
+        }
+
+        let first_endpoint = if token.is_keyword("subgraph") || token.kind == TokenKind::OpenBrace {
+            self.parse_subgraph(scope)?
+        } else {
+            let id = self.parse_id()?;
+
+            if self.eat(&TokenKind::Equals) {
+                let value = self.parse_id()?;
+
+                self.set_graph_attributes(
+                    scope,
+                    &[Attribute {
+                        key: id,
+                        value,
+                        line: token.line,
+                        column: token.column,
+                    }],
+                );
+
+                return Ok(Vec::new());
+            }
+
+            self.parse_port()?;
+
+            if !matches!(
+                self.peek().kind,
+                TokenKind::DirectedEdge | TokenKind::UndirectedEdge
+            ) {
+                let attributes = self.parse_attribute_lists()?;
+                let index = self.mention_node(&id, scope);
+
+                self.document.nodes[index].attributes.extend(attributes);
+
+                return Ok(vec![id]);
+            }
+
+            self.mention_node(&id, scope);
+            vec![id]
+        };
+
+        self.parse_edges(scope, first_endpoint)
+    }
+
+    // Parses the rest of an edge statement after its first node or subgraph.
+    fn parse_edges(
+        &mut self,
+        scope: &mut Scope,
+        first_endpoint: Vec<String>,
+    ) -> Result<Vec<String>, DotError> {
+        let mut endpoints = vec![first_endpoint];
+        let mut operators = Vec::new();
+
+        while let TokenKind::DirectedEdge | TokenKind::UndirectedEdge = self.peek().kind {
+            let operator = self.advance();
+            let is_directed = operator.kind == TokenKind::DirectedEdge;
+
+            if is_directed != self.document.is_directed {
+                return Err(operator.error(if is_directed {
+                    "`->` can only be used in a digraph, use `--` instead"
+                } else {
+                    "`--` can only be used in an undirected graph, use `->` instead"
+                }));
+            }
+
+            let endpoint =
+                if self.peek().is_keyword("subgraph") || self.peek().kind == TokenKind::OpenBrace {
+                    self.parse_subgraph(scope)?
+                } else {
+                    let id = self.parse_id()?;
+
+                    self.parse_port()?;
+                    self.mention_node(&id, scope);
+                    vec![id]
+                };
+
+            endpoints.push(endpoint);
+            operators.push(operator);
+        }
+
+        let mut attributes = scope.edge_attributes.clone();
+        attributes.extend(self.parse_attribute_lists()?);
+
+        for (pair, operator) in endpoints.windows(2).zip(&operators) {
+            for from in &pair[0] {
+                for to in &pair[1] {
+                    self.document.edges.push(DotEdge {
+                        from: from.clone(),
+                        to: to.clone(),
+                        attributes: attributes.clone(),
+                        line: operator.line,
+                        column: operator.column,
+                    });
+                }
+            }
+        }
+
+        Ok(endpoints.concat())
+    }
+
+    fn parse_subgraph(&mut self, scope: &Scope) -> Result<Vec<String>, DotError> {
+        let mut name = None;
+
+        if self.peek().is_keyword("subgraph") {
+            self.advance();
+
+            if matches!(self.peek().kind, TokenKind::Id { .. }) {
+                name = Some(self.parse_id()?);
+            }
+        }
+
+        let mut inner_scope = scope.clone();
+        inner_scope.is_cluster_subgraph = false;
+
+        if let Some(name) = name.filter(|name| name.starts_with("cluster")) {
+            inner_scope.cluster = Some(name);
+            inner_scope.is_cluster_subgraph = true;
+        }
+
+        let open_brace = self.expect(TokenKind::OpenBrace)?;
+
+        if self.depth == MAX_SUBGRAPH_DEPTH {
+            return Err(open_brace.error(format!(
+                "subgraphs cannot be nested more than {} deep",
+                MAX_SUBGRAPH_DEPTH
+            )));
+        }
+
+        self.depth += 1;
+
+        let node_ids = self.parse_statements(&mut inner_scope)?;
+
+        self.depth -= 1;
+        self.expect(TokenKind::CloseBrace)?;
+
+        Ok(node_ids)
+    }
+
+    // Zero or more bracketed attribute lists, run together.
+    fn parse_attribute_lists(&mut self) -> Result<Vec<Attribute>, DotError> {
+        let mut attributes = Vec::new();
+
+        while self.eat(&TokenKind::OpenBracket) {
+            while !self.eat(&TokenKind::CloseBracket) {
+                let token = self.peek().clone();
+                let key = self.parse_id()?;
+
+                self.expect(TokenKind::Equals)?;
+
+                let value = self.parse_id()?;
+
+                attributes.push(Attribute {
+                    key,
+                    value,
+                    line: token.line,
+                    column: token.column,
+                });
+
+                if !self.eat(&TokenKind::Comma) {
+                    self.eat(&TokenKind::Semicolon);
+                }
+            }
+        }
+
+        Ok(attributes)
+    }
+
+    // Ports say where on a node an edge attaches. They are read but not used.
+    fn parse_port(&mut self) -> Result<(), DotError> {
+        for _ in 0..2 {
+            if !self.eat(&TokenKind::Colon) {
+                break;
+            }
+
+            self.parse_id()?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_id(&mut self) -> Result<String, DotError> {
+        let token = self.peek().clone();
+
+        let TokenKind::Id {
+            mut text,
+            is_quoted,
+        } = token.kind.clone()
+        else {
+            return Err(self.unexpected("an id"));
+        };
+
+        if !is_quoted
+            && ["node", "edge", "graph", "digraph", "subgraph", "strict"]
+                .iter()
+                .any(|&keyword| token.is_keyword(keyword))
+        {
+            return Err(token.error(format!(
+                "`{}` is a keyword and must be quoted to be used as an id",
+                text
+            )));
+        }
+
+        self.advance();
+
+        // Quoted strings can be joined with `+`.
+        while is_quoted && self.eat(&TokenKind::Plus) {
+            match self.advance() {
+                Token {
+                    kind:
+                        TokenKind::Id {
+                            text: next_text,
+                            is_quoted: true,
+                        },
+                    ..
+                } => text.push_str(&next_text),
+                token => {
+                    return Err(
+                        token.error(format!("expected a quoted string but found {}", token.kind))
+                    )
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    fn set_graph_attributes(&mut self, scope: &Scope, attributes: &[Attribute]) {
+        let Some(cluster) = scope.cluster.as_ref().filter(|_| scope.is_cluster_subgraph) else {
+            return;
+        };
+
+        for attribute in attributes
+            .iter()
+            .filter(|attribute| attribute.key == "label")
+        {
+            self.document
+                .cluster_labels
+                .insert(cluster.clone(), attribute.value.clone());
+        }
+    }
+
+    // Adds a node the first time it is seen, with the node defaults at that point.
+    fn mention_node(&mut self, id: &str, scope: &Scope) -> usize {
+        if let Some(&index) = self.document.node_indices.get(id) {
+            let node = &mut self.document.nodes[index];
+
+            if node.cluster.is_none() {
+                node.cluster.clone_from(&scope.cluster);
+            }
+
+            return index;
+        }
+
+        let index = self.document.nodes.len();
+
+        self.document.nodes.push(DotNode {
+            id: id.to_string(),
+            attributes: scope.node_attributes.clone(),
+            cluster: scope.cluster.clone(),
+        });
+        self.document.node_indices.insert(id.to_string(), index);
+
+        index
+    }
+}
+
+// The last value given for an attribute, as later values replace earlier ones.
+fn attribute<'a>(attributes: &'a [Attribute], key: &str) -> Option<&'a Attribute> {
+    attributes
+        .iter()
+        .rev()
+        .find(|attribute| attribute.key == key)
+}
+
+fn build_graph(document: &Document) -> Result<Graph, DotError> {
+    let mut graph = Graph::new();
+    let mut node_ids: HashMap<&str, NodeId> = HashMap::new();
+
+    for node in &document.nodes {
+        let label = attribute(&node.attributes, "label").map_or_else(
+            || node.id.clone(),
+            |label| label.value.replace("\\N", &node.id),
+        );
+        let node_id = graph.insert_node(&label);
+
+        graph.set_node_style(node_id, node_style(&node.attributes)?);
+
+        if let Some(cluster) = &node.cluster {
+            let cluster_label = document.cluster_labels.get(cluster).unwrap_or(cluster);
+
+            graph.set_node_cluster(node_id, Some(cluster_label));
+        }
+
+        for attribute in &node.attributes {
+            if !STYLE_ATTRIBUTES.contains(&attribute.key.as_str()) {
+                graph.set_node_metadata(node_id, &attribute.key, &attribute.value);
+            }
+        }
+
+        node_ids.insert(&node.id, node_id);
+    }
+
+    for edge in &document.edges {
+        let (from, to) = (node_ids[edge.from.as_str()], node_ids[edge.to.as_str()]);
+
+        if document.is_strict && !graph.edges_between(from, to).is_empty() {
+            continue;
+        }
+
+        // Graphviz allows a weight of 0 for edges that should not pull their nodes into place,
+        // which no spring can do, so such edges get the default weight instead.
+        let weight = attribute(&edge.attributes, "weight");
+        let weight_value = weight
+            .map(Attribute::number)
+            .transpose()?
+            .filter(|&weight_value| weight_value != 0.0)
+            .unwrap_or(1.0);
+        let result = if document.is_directed {
+            graph.try_add_weighted_edge(from, to, weight_value)
+        } else {
+            graph.try_add_weighted_undirected_edge(from, to, weight_value)
+        };
+        let edge_id = result.map_err(|error| match weight {
+            Some(weight) => weight.error(error.to_string()),
+            None => DotError {
+                line: edge.line,
+                column: edge.column,
+                message: error.to_string(),
+            },
+        })?;
+
+        if let Some(label) = attribute(&edge.attributes, "label") {
+            graph.set_edge_label(edge_id, Some(&label.value));
+        }
+
+        let style = edge_style(&edge.attributes, *graph.default_edge_style())?;
+
+        if style != *graph.default_edge_style() {
+            graph.set_edge_style(edge_id, Some(style));
+        }
+    }
+
+    Ok(graph)
+}
+
+fn node_style(attributes: &[Attribute]) -> Result<NodeStyle, DotError> {
+    let mut style = NodeStyle::default();
+    let style_flags: Vec<&str> = attribute(attributes, "style")
+        .map(|style| style.value.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    let color = attribute(attributes, "color").and_then(|color| parse_color(&color.value));
+
+    if let Some(shape) = attribute(attributes, "shape") {
+        style.shape = match shape.value.as_str() {
+            "box" | "rect" | "rectangle" | "square" | "record" => {
+                if style_flags.contains(&"rounded") {
+                    NodeShape::RoundedRect
+                } else {
+                    NodeShape::Square
+                }
+            }
+            "Mrecord" => NodeShape::RoundedRect,
+            "diamond" => NodeShape::Diamond,
+            _ => NodeShape::Circle,
+        };
+    }
+
+    if let Some(color) = color {
+        style.stroke = color;
+        style.stroke_width = DEFAULT_STROKE_WIDTH;
+
+        if style_flags.contains(&"filled") {
+            style.fill = color;
+        }
+    }
+
+    if let Some(fill) = attribute(attributes, "fillcolor").and_then(|fill| parse_color(&fill.value))
+    {
+        style.fill = fill;
+    }
+
+    if let Some(label_color) =
+        attribute(attributes, "fontcolor").and_then(|color| parse_color(&color.value))
+    {
+        style.label_color = label_color;
+    }
+
+    if let Some(font_size) = attribute(attributes, "fontsize") {
+        style.font_size = font_size.number()?.round().clamp(1.0, u16::MAX as f32) as u16;
+    }
+
+    if let Some(pen_width) = attribute(attributes, "penwidth") {
+        style.stroke_width = pen_width.number()?.max(0.0);
+    }
+
+    Ok(style)
+}
+
+fn edge_style(attributes: &[Attribute], default: EdgeStyle) -> Result<EdgeStyle, DotError> {
+    let mut style = default;
+
+    if let Some(color) = attribute(attributes, "color").and_then(|color| parse_color(&color.value))
+    {
+        style.color = color;
+    }
+
+    if let Some(pen_width) = attribute(attributes, "penwidth") {
+        style.width = pen_width.number()?.max(0.0);
+    }
+
+    if let Some(line_style) = attribute(attributes, "style") {
+        for flag in line_style.value.split(',').map(str::trim) {
+            match flag {
+                "dashed" => style.pattern = LinePattern::Dashed,
+                "dotted" => style.pattern = LinePattern::Dotted,
+                "solid" => style.pattern = LinePattern::Solid,
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(arrowhead) = attribute(attributes, "arrowhead") {
+        style.arrowhead = match arrowhead.value.as_str() {
+            "normal" | "inv" => ArrowHead::Filled,
+            "empty" | "onormal" | "vee" | "open" => ArrowHead::Open,
+            "diamond" | "odiamond" => ArrowHead::Diamond,
+            "dot" | "odot" => ArrowHead::Circle,
+            "none" => ArrowHead::None,
+            _ => style.arrowhead,
+        };
+    }
+
+    match attribute(attributes, "dir").map(|dir| dir.value.as_str()) {
+        Some("both") => style.both_ends = true,
+        Some("none") => {
+            style.arrowhead = ArrowHead::None;
+            style.both_ends = false;
+        }
+        _ => {}
+    }
+
+    Ok(style)
+}
+
+// Reads `#rrggbb`, `#rrggbbaa` or one of the common X11 colour names. Of a list of colours, as
+// used for multicoloured edges, only the first is read.
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.split(':').next()?.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+
+        return match hex.len() {
+            6 => Some(Color::from_rgba(channel(0)?, channel(2)?, channel(4)?, 255)),
+            8 => Some(Color::from_rgba(
+                channel(0)?,
+                channel(2)?,
+                channel(4)?,
+                channel(6)?,
+            )),
+            _ => None,
+        };
+    }
+
+    let (r, g, b) = match value.to_ascii_lowercase().as_str() {
+        "black" => (0, 0, 0),
+        "white" => (255, 255, 255),
+        "gray" | "grey" => (190, 190, 190),
+        "lightgray" | "lightgrey" => (211, 211, 211),
+        "darkgray" | "darkgrey" => (169, 169, 169),
+        "red" => (255, 0, 0),
+        "green" => (0, 255, 0),
+        "darkgreen" => (0, 100, 0),
+        "blue" => (0, 0, 255),
+        "lightblue" => (173, 216, 230),
+        "skyblue" => (135, 206, 235),
+        "navy" => (0, 0, 128),
+        "cyan" => (0, 255, 255),
+        "magenta" => (255, 0, 255),
+        "yellow" => (255, 255, 0),
+        "gold" => (255, 215, 0),
+        "orange" => (255, 165, 0),
+        "purple" => (160, 32, 240),
+        "violet" => (238, 130, 238),
+        "pink" => (255, 192, 203),
+        "brown" => (165, 42, 42),
+        "maroon" => (176, 48, 96),
+        "beige" => (245, 245, 220),
+        "transparent" | "none" => return Some(Color::from_rgba(0, 0, 0, 0)),
+        _ => return None,
+    };
+
+    Some(Color::from_rgba(r, g, b, 255))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_read_nodes_and_edges_with_attributes() {
+        let graph = parse(
+            r##"
+            // The order tasks run in.
+            digraph tasks {
+                a [label="Fetch", shape=box, color=red, fillcolor="#00ff0080", owner=ops];
+                a -> b -> c [weight=2, label="then"];
+                c -> a [style=dashed, color=blue, arrowhead=normal];
+            }
+            "##,
+        )
+        .unwrap();
+
+        let fetch = graph.node_id("Fetch").unwrap();
+        let b = graph.node_id("b").unwrap();
+        let c = graph.node_id("c").unwrap();
+        let fetch_style = graph.node_style(fetch).unwrap();
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(fetch_style.shape, NodeShape::Square);
+        assert_eq!(fetch_style.stroke, Color::from_rgba(255, 0, 0, 255));
+        assert_eq!(fetch_style.fill, Color::from_rgba(0, 255, 0, 128));
+        assert_eq!(graph.node_metadata(fetch, "owner"), Some("ops"));
+        assert_eq!(graph.node_metadata(fetch, "label"), None);
+
+        let fetch_to_b = graph.edges_between(fetch, b)[0];
+        let c_to_fetch = graph.edges_between(c, fetch)[0];
+
+        assert_eq!(graph.edges[&fetch_to_b.0].weight, 2.0);
+        assert!(graph.edges[&fetch_to_b.0].is_directed);
+        assert_eq!(graph.edge_label(graph.edges_between(b, c)[0]), Some("then"));
+        assert_eq!(graph.edge_style(fetch_to_b), Some(&EdgeStyle::default()));
+
+        let style = graph.edge_style(c_to_fetch).unwrap();
+
+        assert_eq!(style.pattern, LinePattern::Dashed);
+        assert_eq!(style.arrowhead, ArrowHead::Filled);
+        assert_eq!(style.color, Color::from_rgba(0, 0, 255, 255));
+    }
+
+    #[test]
+    fn should_read_subgraphs_as_clusters() {
+        let graph = parse(
+            r#"
+            graph {
+                node [shape=diamond];
+                subgraph cluster_backend {
+                    label = "Backend";
+                    node [fontsize=30];
+                    api; db;
+                }
+                subgraph cluster_frontend { web }
+                api -- db;
+                web -- { api db };
+                user;
+            }
+            "#,
+        )
+        .unwrap();
+
+        let api = graph.node_id("api").unwrap();
+        let web = graph.node_id("web").unwrap();
+        let user = graph.node_id("user").unwrap();
+
+        assert_eq!(graph.node_cluster(api), Some("Backend"));
+        assert_eq!(graph.node_cluster(web), Some("cluster_frontend"));
+        assert_eq!(graph.node_cluster(user), None);
+        assert_eq!(graph.node_style(api).unwrap().font_size, 30);
+        assert_eq!(graph.node_style(user).unwrap().font_size, 20);
+        assert_eq!(graph.node_style(user).unwrap().shape, NodeShape::Diamond);
+        assert_eq!(graph.edges.len(), 3);
+        assert!(graph.edges.values().all(|edge| !edge.is_directed));
+        assert_eq!(graph.edges_between(api, web).len(), 1);
+    }
+
+    #[test]
+    fn should_replace_the_node_id_escape_in_labels() {
+        let graph = parse(r#"digraph { node [label="\N!"]; a; b [label="Node \N"] }"#).unwrap();
+
+        assert!(graph.node_id("a!").is_some());
+        assert!(graph.node_id("Node b").is_some());
+    }
+
+    #[test]
+    fn should_only_name_clusters_from_their_own_label() {
+        let graph =
+            parse("digraph { subgraph cluster_a { label=A; subgraph inner { label=Oops; x } } }")
+                .unwrap();
+
+        assert_eq!(graph.node_cluster(graph.node_id("x").unwrap()), Some("A"));
+    }
+
+    #[test]
+    fn should_read_quoted_ids_ports_comments_and_strict_graphs() {
+        let graph = parse(
+            "strict digraph {\n\
+             # 1 \"preprocessed.dot\"\n\
+             /* A block\n   comment */\n\
+             \"say \\\"hi\\\"\" + \" there\" -> b:port:n;\n\
+             b -> <<b>bold</b>>;\n\
+             \"say \\\"hi\\\" there\" -> b;\n\
+             -1.5 -> b\n\
+             }",
+        )
+        .unwrap();
+
+        let greeting = graph.node_id("say \"hi\" there").unwrap();
+        let b = graph.node_id("b").unwrap();
+
+        assert!(graph.node_id("<b>bold</b>").is_some());
+        assert!(graph.node_id("-1.5").is_some());
+        assert_eq!(graph.edges_between(greeting, b).len(), 1);
+    }
+
+    #[test]
+    fn should_give_edges_with_zero_weight_the_default_weight() {
+        let graph = parse("digraph { a -> b [weight=0]; a -> c [weight=3] }").unwrap();
+
+        let mut weights: Vec<f32> = graph.edges.values().map(|edge| edge.weight).collect();
+        weights.sort_by(f32::total_cmp);
+
+        assert_eq!(weights, vec![1.0, 3.0]);
+    }
+
+    #[test]
+    fn should_report_where_errors_are() {
+        let error = |input: &str| {
+            let error = parse(input).unwrap_err();
+
+            (error.line, error.column, error.message)
+        };
+
+        assert_eq!(
+            error("digraph {\n  a -> ;\n}"),
+            (2, 8, "expected an id but found `;`".to_string())
+        );
+        assert_eq!(
+            error("graph {\n  a -> b\n}"),
+            (
+                2,
+                5,
+                "`->` can only be used in a digraph, use `--` instead".to_string()
+            )
+        );
+        assert_eq!(
+            error("digraph {\n  a [label=\"open\n}"),
+            (2, 12, "unterminated string".to_string())
+        );
+        assert_eq!(
+            error("digraph {\n  a -> b [weight=-1]\n}"),
+            (2, 11, "edge weights must be positive, got -1".to_string())
+        );
+        assert_eq!(
+            error(&format!("digraph {{\n  {}", "{".repeat(100_000))),
+            (
+                2,
+                MAX_SUBGRAPH_DEPTH + 3,
+                format!(
+                    "subgraphs cannot be nested more than {} deep",
+                    MAX_SUBGRAPH_DEPTH
+                )
+            )
+        );
+        assert_eq!(
+            error("digraph {\n  a [fontsize=big]\n}"),
+            (2, 6, "`fontsize` must be a number, got \"big\"".to_string())
+        );
+        assert_eq!(
+            error("tree { a }"),
+            (
+                1,
+                1,
+                "expected `graph` or `digraph` but found \"tree\"".to_string()
+            )
+        );
+        assert_eq!(
+            error("digraph { a } b"),
+            (
+                1,
+                15,
+                "expected the end of the file but found \"b\"".to_string()
+            )
+        );
+    }
+}
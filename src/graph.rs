@@ -1,21 +1,26 @@
-use crate::physics::physics_update;
-use crate::renderer;
-use crate::renderer::{render, ScrollableView};
-use hecs::World;
-use macroquad::color::BLACK;
+use crate::physics::PhysicsConfig;
+use crate::style::{EdgeStyle, NodeStyle};
+use crate::view::GraphView;
+pub use crate::view::{GraphUpdate, SelectionEvent};
+use hecs::{Entity, World};
+use macroquad::math::{Rect, Vec2};
 use macroquad::prelude::next_frame;
 use macroquad::prelude::Conf;
+use macroquad::time::get_frame_time;
 use macroquad::window::{screen_height, screen_width};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
 use std::f32::consts::PI;
+use std::fmt;
+use std::sync::mpsc::Receiver;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Velocity {
     pub(crate) x: f32,
     pub(crate) y: f32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Force {
     pub(crate) x: f32,
     pub(crate) y: f32,
@@ -27,15 +32,37 @@ pub(crate) struct Position {
     pub(crate) y: f32,
 }
 
-#[derive(Debug)]
+// Marks a node the physics leaves in place, either pinned by the user or held by a drag.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pinned;
+
+// Marks a node the physics leaves in place while the simulation only runs around a change to the
+// graph, until it settles again.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Frozen;
+
+// The name of the cluster a node is drawn grouped with.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Cluster(pub(crate) String);
+
+// Key and value pairs shown in a node's tooltip, in the order they were first set.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Metadata(pub(crate) Vec<(String, String)>);
+
+#[derive(Debug, Clone)]
 pub(crate) struct Size {
     pub(crate) radius: f32,
 }
 
 #[derive(Debug)]
-pub(crate) struct Node {
+pub(crate) struct Node<N> {
     pub(crate) id: usize,
     pub(crate) label: String,
+    pub(crate) data: N,
+    pub(crate) style: NodeStyle,
+    pub(crate) is_pinned: bool,
+    pub(crate) cluster: Option<String>,
+    pub(crate) metadata: Metadata,
     pub(crate) physics_data: PhysicsData,
     pub(crate) outgoing_directed_edges: HashSet<usize>,
     pub(crate) incoming_directed_edges: HashSet<usize>,
@@ -48,13 +75,18 @@ pub(crate) struct Edge {
     pub(crate) source_node_id: usize,
     pub(crate) destination_node_id: usize,
     pub(crate) is_directed: bool,
+    pub(crate) weight: f32,
+    // Which of several edges between the same nodes this is, counted outwards from the straight
+    // line between them. For an edge from a node to itself it is the index of the loop instead.
+    pub(crate) lane: f32,
+    pub(crate) label: Option<String>,
+    pub(crate) style: EdgeStyle,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct PhysicsData {
     pub(crate) velocity: Velocity,
     pub(crate) force: Force,
-    pub(crate) size: Size,
 }
 
 impl PhysicsData {
@@ -62,107 +94,618 @@ impl PhysicsData {
         Self {
             velocity: Velocity { x: 0.0, y: 0.0 },
             force: Force { x: 0.0, y: 0.0 },
-            size: Size { radius: 15.0 },
         }
     }
 }
 
+/// Handle to a node in a [`Graph`]. Ids are never reused, even after the node is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub(crate) usize);
+
+/// Anything that identifies a node in a [`Graph`]: either its [`NodeId`] or its label.
+pub trait NodeKey {
+    fn resolve<N, E>(&self, graph: &Graph<N, E>) -> Result<NodeId, GraphError>;
+}
+
+impl NodeKey for NodeId {
+    fn resolve<N, E>(&self, graph: &Graph<N, E>) -> Result<NodeId, GraphError> {
+        if graph.nodes.contains_key(&self.0) {
+            Ok(*self)
+        } else {
+            Err(GraphError::UnknownNodeId(*self))
+        }
+    }
+}
+
+impl NodeKey for str {
+    fn resolve<N, E>(&self, graph: &Graph<N, E>) -> Result<NodeId, GraphError> {
+        graph
+            .node_id(self)
+            .ok_or_else(|| GraphError::NodeNotFound(self.to_string()))
+    }
+}
+
+impl NodeKey for String {
+    fn resolve<N, E>(&self, graph: &Graph<N, E>) -> Result<NodeId, GraphError> {
+        self.as_str().resolve(graph)
+    }
+}
+
+impl<T: NodeKey + ?Sized> NodeKey for &T {
+    fn resolve<N, E>(&self, graph: &Graph<N, E>) -> Result<NodeId, GraphError> {
+        (**self).resolve(graph)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    NodeNotFound(String),
+    UnknownNodeId(NodeId),
+    DuplicateLabel(String),
+    InvalidWeight(f32),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::NodeNotFound(label) => write!(f, "no node is labelled \"{}\"", label),
+            GraphError::UnknownNodeId(id) => write!(f, "no node has id {}", id.0),
+            GraphError::DuplicateLabel(label) => {
+                write!(f, "a node labelled \"{}\" already exists", label)
+            }
+            GraphError::InvalidWeight(weight) => {
+                write!(f, "edge weights must be positive, got {}", weight)
+            }
+        }
+    }
+}
+
+impl Error for GraphError {}
+
+/// Handle to an edge in a [`Graph`]. Ids are never reused, even after the edge is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(pub(crate) usize);
+
 #[derive(Debug)]
-pub struct Graph {
-    pub(crate) nodes: Vec<Node>,
+pub(crate) struct EdgeRecord<E> {
+    pub(crate) source_node_id: usize,
+    pub(crate) destination_node_id: usize,
+    pub(crate) is_directed: bool,
+    pub(crate) data: E,
+    pub(crate) weight: f32,
+    pub(crate) label: Option<String>,
+    pub(crate) style: Option<EdgeStyle>,
+}
+
+impl<E> EdgeRecord<E> {
+    fn connects(&self, from_id: usize, to_id: usize) -> bool {
+        (self.source_node_id == from_id && self.destination_node_id == to_id)
+            || (!self.is_directed
+                && self.source_node_id == to_id
+                && self.destination_node_id == from_id)
+    }
+}
+
+/// A graph whose nodes carry data of type `N` and whose edges carry data of type `E`. Any number
+/// of edges may join the same pair of nodes, and an edge may start and end at the same node.
+///
+/// Both default to `()`, so a plain `Graph` carries no data beyond the `String` label every node
+/// has.
+#[derive(Debug)]
+pub struct Graph<N = (), E = ()> {
+    pub(crate) nodes: BTreeMap<usize, Node<N>>,
     pub(crate) node_lookup: HashMap<String, usize>,
+    pub(crate) edges: BTreeMap<usize, EdgeRecord<E>>,
+    default_edge_style: EdgeStyle,
+    next_node_id: usize,
+    next_edge_id: usize,
 }
 
-impl Graph {
-    pub fn new() -> Self {
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
         Self {
-            nodes: Vec::new(),
+            nodes: BTreeMap::new(),
             node_lookup: HashMap::new(),
+            edges: BTreeMap::new(),
+            default_edge_style: EdgeStyle::default(),
+            next_node_id: 0,
+            next_edge_id: 0,
+        }
+    }
+}
+
+impl Graph {
+    /// Creates a graph without node or edge data. Use [`Graph::default`] to create a graph
+    /// carrying data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    /// Adds a node carrying `data`, or returns the existing node unchanged if one already has this
+    /// label, like [`Graph::add_node`]. Use [`Graph::node_data_mut`] to change an existing node's
+    /// data.
+    pub fn add_node_with_data(&mut self, label: &str, data: N) -> NodeId {
+        match self.node_id(label) {
+            Some(id) => id,
+            None => self.insert_node_with_data(label, data),
         }
     }
 
-    pub fn add_node(&mut self, label: &str) {
-        let id = self.nodes.len();
+    pub fn try_add_node_with_data(&mut self, label: &str, data: N) -> Result<NodeId, GraphError> {
+        if self.node_lookup.contains_key(label) {
+            return Err(GraphError::DuplicateLabel(label.to_string()));
+        }
+
+        Ok(self.insert_node_with_data(label, data))
+    }
 
-        self.node_lookup.insert(label.to_string(), id);
-        self.nodes.push(Node {
+    /// Always adds a new node, even if another node already has this label. Labels are then only
+    /// display text and [`Graph::node_id`] keeps returning the first node with the label.
+    pub fn insert_node_with_data(&mut self, label: &str, data: N) -> NodeId {
+        let id = self.next_node_id;
+        self.next_node_id += 1;
+
+        self.node_lookup.entry(label.to_string()).or_insert(id);
+        self.nodes.insert(
             id,
-            label: label.to_string(),
-            physics_data: PhysicsData::init(),
-            outgoing_directed_edges: HashSet::new(),
-            incoming_directed_edges: HashSet::new(),
-            outgoing_undirected_edges: HashSet::new(),
-            incoming_undirected_edges: HashSet::new(),
-        });
-    }
-
-    pub fn add_directed_edge(&mut self, from: &str, to: &str) {
-        if from.eq(to) {
-            return;
-        }
+            Node {
+                id,
+                label: label.to_string(),
+                data,
+                style: NodeStyle::default(),
+                is_pinned: false,
+                cluster: None,
+                metadata: Metadata::default(),
+                physics_data: PhysicsData::init(),
+                outgoing_directed_edges: HashSet::new(),
+                incoming_directed_edges: HashSet::new(),
+                outgoing_undirected_edges: HashSet::new(),
+                incoming_undirected_edges: HashSet::new(),
+            },
+        );
+
+        NodeId(id)
+    }
+
+    pub fn node_id(&self, label: &str) -> Option<NodeId> {
+        self.node_lookup.get(label).map(|&id| NodeId(id))
+    }
+
+    pub fn label(&self, id: NodeId) -> Option<&str> {
+        self.nodes.get(&id.0).map(|node| node.label.as_str())
+    }
+
+    pub fn node_data(&self, node: impl NodeKey) -> Option<&N> {
+        let NodeId(id) = node.resolve(self).ok()?;
+
+        self.nodes.get(&id).map(|node| &node.data)
+    }
+
+    pub fn node_data_mut(&mut self, node: impl NodeKey) -> Option<&mut N> {
+        let NodeId(id) = node.resolve(self).ok()?;
+
+        self.nodes.get_mut(&id).map(|node| &mut node.data)
+    }
+
+    /// Pinned nodes are held in place by the simulation but still push and pull on others.
+    /// Returns whether the node exists.
+    pub fn set_pinned(&mut self, node: impl NodeKey, is_pinned: bool) -> bool {
+        let Ok(NodeId(id)) = node.resolve(self) else {
+            return false;
+        };
 
-        if let (Some(&from_id), Some(&to_id)) =
-            (self.node_lookup.get(from), self.node_lookup.get(to))
+        self.nodes.get_mut(&id).unwrap().is_pinned = is_pinned;
+        true
+    }
+
+    pub fn is_pinned(&self, node: impl NodeKey) -> bool {
+        node.resolve(self)
+            .is_ok_and(|NodeId(id)| self.nodes[&id].is_pinned)
+    }
+
+    /// Puts the node in the cluster named `cluster`, or takes it out of its cluster if `None`. The
+    /// nodes of a cluster are drawn inside an outline captioned with its name. Returns whether the
+    /// node exists.
+    pub fn set_node_cluster(&mut self, node: impl NodeKey, cluster: Option<&str>) -> bool {
+        let Ok(NodeId(id)) = node.resolve(self) else {
+            return false;
+        };
+
+        self.nodes.get_mut(&id).unwrap().cluster = cluster.map(str::to_string);
+        true
+    }
+
+    pub fn node_cluster(&self, node: impl NodeKey) -> Option<&str> {
+        let NodeId(id) = node.resolve(self).ok()?;
+
+        self.nodes.get(&id)?.cluster.as_deref()
+    }
+
+    /// Sets a value shown in the node's tooltip, replacing any earlier value for `key`. Returns
+    /// whether the node exists.
+    pub fn set_node_metadata(&mut self, node: impl NodeKey, key: &str, value: &str) -> bool {
+        let Ok(NodeId(id)) = node.resolve(self) else {
+            return false;
+        };
+
+        let Metadata(metadata) = &mut self.nodes.get_mut(&id).unwrap().metadata;
+
+        match metadata
+            .iter_mut()
+            .find(|(existing_key, _)| existing_key == key)
         {
-            self.nodes
-                .get_mut(from_id)
-                .unwrap()
-                .outgoing_directed_edges
-                .insert(to_id);
-            self.nodes
-                .get_mut(to_id)
-                .unwrap()
-                .incoming_directed_edges
-                .insert(from_id);
+            Some((_, existing_value)) => *existing_value = value.to_string(),
+            None => metadata.push((key.to_string(), value.to_string())),
         }
+
+        true
+    }
+
+    pub fn node_metadata(&self, node: impl NodeKey, key: &str) -> Option<&str> {
+        let NodeId(id) = node.resolve(self).ok()?;
+        let Metadata(metadata) = &self.nodes.get(&id)?.metadata;
+
+        metadata
+            .iter()
+            .find(|(existing_key, _)| existing_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn remove_node_metadata(&mut self, node: impl NodeKey, key: &str) -> Option<String> {
+        let NodeId(id) = node.resolve(self).ok()?;
+        let Metadata(metadata) = &mut self.nodes.get_mut(&id)?.metadata;
+        let index = metadata
+            .iter()
+            .position(|(existing_key, _)| existing_key == key)?;
+
+        Some(metadata.remove(index).1)
+    }
+
+    pub fn node_style(&self, node: impl NodeKey) -> Option<&NodeStyle> {
+        let NodeId(id) = node.resolve(self).ok()?;
+
+        self.nodes.get(&id).map(|node| &node.style)
+    }
+
+    pub fn node_style_mut(&mut self, node: impl NodeKey) -> Option<&mut NodeStyle> {
+        let NodeId(id) = node.resolve(self).ok()?;
+
+        self.nodes.get_mut(&id).map(|node| &mut node.style)
     }
 
-    pub fn add_undirected_edge(&mut self, from: &str, to: &str) {
-        if from.eq(to) {
-            return;
+    /// Replaces how a node is drawn, returning whether the node exists.
+    pub fn set_node_style(&mut self, node: impl NodeKey, style: NodeStyle) -> bool {
+        match self.node_style_mut(node) {
+            Some(node_style) => {
+                *node_style = style;
+                true
+            }
+            None => false,
         }
+    }
 
-        if let (Some(&from_id), Some(&to_id)) =
-            (self.node_lookup.get(from), self.node_lookup.get(to))
-        {
-            self.nodes
-                .get_mut(from_id)
-                .unwrap()
-                .outgoing_undirected_edges
-                .insert(to_id);
-            self.nodes
-                .get_mut(to_id)
-                .unwrap()
-                .incoming_undirected_edges
-                .insert(from_id);
+    /// Adds an edge from `from` to `to` carrying `data`, ignoring it if either node is missing.
+    pub fn add_directed_edge_with_data(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        data: E,
+    ) -> Option<EdgeId> {
+        self.try_add_directed_edge_with_data(from, to, data).ok()
+    }
+
+    pub fn try_add_directed_edge_with_data(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        data: E,
+    ) -> Result<EdgeId, GraphError> {
+        let (from_id, to_id) = self.edge_endpoints(&from, &to)?;
+
+        Ok(self.connect(from_id, to_id, true, data, 1.0))
+    }
+
+    /// Adds an edge between `from` and `to` carrying `data`, ignoring it if either node is
+    /// missing.
+    pub fn add_undirected_edge_with_data(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        data: E,
+    ) -> Option<EdgeId> {
+        self.try_add_undirected_edge_with_data(from, to, data).ok()
+    }
+
+    pub fn try_add_undirected_edge_with_data(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        data: E,
+    ) -> Result<EdgeId, GraphError> {
+        let (from_id, to_id) = self.edge_endpoints(&from, &to)?;
+
+        Ok(self.connect(from_id, to_id, false, data, 1.0))
+    }
+
+    fn connect(
+        &mut self,
+        from_id: usize,
+        to_id: usize,
+        is_directed: bool,
+        data: E,
+        weight: f32,
+    ) -> EdgeId {
+        let id = self.next_edge_id;
+        self.next_edge_id += 1;
+
+        let from_node = self.nodes.get_mut(&from_id).unwrap();
+
+        if is_directed {
+            from_node.outgoing_directed_edges.insert(id);
+        } else {
+            from_node.outgoing_undirected_edges.insert(id);
+        }
+
+        let to_node = self.nodes.get_mut(&to_id).unwrap();
+
+        if is_directed {
+            to_node.incoming_directed_edges.insert(id);
+        } else {
+            to_node.incoming_undirected_edges.insert(id);
+        }
+
+        self.edges.insert(
+            id,
+            EdgeRecord {
+                source_node_id: from_id,
+                destination_node_id: to_id,
+                is_directed,
+                data,
+                weight,
+                label: None,
+                style: None,
+            },
+        );
+
+        EdgeId(id)
+    }
+
+    /// Sets the text drawn next to the middle of an edge, or removes it when `label` is `None`.
+    /// Returns whether the edge exists.
+    pub fn set_edge_label(&mut self, edge: EdgeId, label: Option<&str>) -> bool {
+        match self.edges.get_mut(&edge.0) {
+            Some(edge) => {
+                edge.label = label.map(str::to_string);
+                true
+            }
+            None => false,
         }
     }
 
-    fn get_node_by_name(&self, name: &str) -> Option<&Node> {
+    pub fn edge_label(&self, edge: EdgeId) -> Option<&str> {
+        self.edges.get(&edge.0)?.label.as_deref()
+    }
+
+    /// Gives an edge a style of its own, or makes it follow the graph's default edge style again
+    /// when `style` is `None`. Returns whether the edge exists.
+    pub fn set_edge_style(&mut self, edge: EdgeId, style: Option<EdgeStyle>) -> bool {
+        match self.edges.get_mut(&edge.0) {
+            Some(edge) => {
+                edge.style = style;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The style an edge is drawn with, which is the graph's default unless it has its own.
+    pub fn edge_style(&self, edge: EdgeId) -> Option<&EdgeStyle> {
+        let edge = self.edges.get(&edge.0)?;
+
+        Some(edge.style.as_ref().unwrap_or(&self.default_edge_style))
+    }
+
+    pub fn default_edge_style(&self) -> &EdgeStyle {
+        &self.default_edge_style
+    }
+
+    /// Changes how every edge without a style of its own is drawn.
+    pub fn set_default_edge_style(&mut self, style: EdgeStyle) {
+        self.default_edge_style = style;
+    }
+
+    pub fn edge_data(&self, edge: EdgeId) -> Option<&E> {
+        self.edges.get(&edge.0).map(|edge| &edge.data)
+    }
+
+    pub fn edge_data_mut(&mut self, edge: EdgeId) -> Option<&mut E> {
+        self.edges.get_mut(&edge.0).map(|edge| &mut edge.data)
+    }
+
+    /// The source and destination of an edge, in the order they were given when it was added.
+    pub fn edge_nodes(&self, edge: EdgeId) -> Option<(NodeId, NodeId)> {
+        self.edges.get(&edge.0).map(|edge| {
+            (
+                NodeId(edge.source_node_id),
+                NodeId(edge.destination_node_id),
+            )
+        })
+    }
+
+    /// Every directed edge from `from` to `to` and every undirected edge between the two nodes.
+    pub fn edges_between(&self, from: impl NodeKey, to: impl NodeKey) -> Vec<EdgeId> {
+        let Ok((from_id, to_id)) = self.edge_endpoints(&from, &to) else {
+            return Vec::new();
+        };
+
+        self.edges
+            .iter()
+            .filter(|(_, edge)| edge.connects(from_id, to_id))
+            .map(|(&id, _)| EdgeId(id))
+            .collect()
+    }
+
+    /// Data of the first directed edge from `from` to `to`.
+    pub fn directed_edge_data(&self, from: impl NodeKey, to: impl NodeKey) -> Option<&E> {
+        let (from_id, to_id) = self.edge_endpoints(&from, &to).ok()?;
+
+        self.edges
+            .values()
+            .find(|edge| {
+                edge.is_directed
+                    && edge.source_node_id == from_id
+                    && edge.destination_node_id == to_id
+            })
+            .map(|edge| &edge.data)
+    }
+
+    /// Data of the first undirected edge between `from` and `to`.
+    pub fn undirected_edge_data(&self, from: impl NodeKey, to: impl NodeKey) -> Option<&E> {
+        let (from_id, to_id) = self.edge_endpoints(&from, &to).ok()?;
+
+        self.edges
+            .values()
+            .find(|edge| !edge.is_directed && edge.connects(from_id, to_id))
+            .map(|edge| &edge.data)
+    }
+
+    fn edge_endpoints(
+        &self,
+        from: &impl NodeKey,
+        to: &impl NodeKey,
+    ) -> Result<(usize, usize), GraphError> {
+        let NodeId(from_id) = from.resolve(self)?;
+        let NodeId(to_id) = to.resolve(self)?;
+
+        Ok((from_id, to_id))
+    }
+
+    pub fn remove_node(&mut self, node: impl NodeKey) -> bool {
+        let Ok(NodeId(id)) = node.resolve(self) else {
+            return false;
+        };
+        let Some(node) = self.nodes.remove(&id) else {
+            return false;
+        };
+
+        if self.node_lookup.get(&node.label) == Some(&id) {
+            let next_with_label = self
+                .nodes
+                .values()
+                .find(|other| other.label == node.label)
+                .map(|other| other.id);
+
+            match next_with_label {
+                Some(next_id) => self.node_lookup.insert(node.label.clone(), next_id),
+                None => self.node_lookup.remove(&node.label),
+            };
+        }
+
+        let incident_edges = node
+            .outgoing_directed_edges
+            .iter()
+            .chain(&node.incoming_directed_edges)
+            .chain(&node.outgoing_undirected_edges)
+            .chain(&node.incoming_undirected_edges);
+
+        for &edge_id in incident_edges {
+            self.disconnect(edge_id);
+        }
+
+        true
+    }
+
+    /// Removes every directed edge from `from` to `to` and every undirected edge between the two
+    /// nodes, returning whether an edge was removed.
+    pub fn remove_edge(&mut self, from: impl NodeKey, to: impl NodeKey) -> bool {
+        let edge_ids = self.edges_between(from, to);
+
+        for &EdgeId(edge_id) in &edge_ids {
+            self.disconnect(edge_id);
+        }
+
+        !edge_ids.is_empty()
+    }
+
+    pub fn remove_edge_by_id(&mut self, edge: EdgeId) -> bool {
+        self.disconnect(edge.0)
+    }
+
+    fn disconnect(&mut self, edge_id: usize) -> bool {
+        let Some(edge) = self.edges.remove(&edge_id) else {
+            return false;
+        };
+
+        if let Some(node) = self.nodes.get_mut(&edge.source_node_id) {
+            node.outgoing_directed_edges.remove(&edge_id);
+            node.outgoing_undirected_edges.remove(&edge_id);
+        }
+
+        if let Some(node) = self.nodes.get_mut(&edge.destination_node_id) {
+            node.incoming_directed_edges.remove(&edge_id);
+            node.incoming_undirected_edges.remove(&edge_id);
+        }
+
+        true
+    }
+
+    pub fn clear_edges(&mut self) {
+        for node in self.nodes.values_mut() {
+            node.outgoing_directed_edges.clear();
+            node.incoming_directed_edges.clear();
+            node.outgoing_undirected_edges.clear();
+            node.incoming_undirected_edges.clear();
+        }
+
+        self.edges.clear();
+    }
+
+    #[cfg(test)]
+    fn get_node_by_name(&self, name: &str) -> Option<&Node<N>> {
         self.node_lookup
             .get(name)
-            .and_then(|&index| self.nodes.get(index))
+            .and_then(|index| self.nodes.get(index))
     }
 
-    fn get_all_edges(&self) -> Vec<Edge> {
-        let mut edges = Vec::new();
+    pub(crate) fn get_all_edges(&self) -> Vec<Edge> {
+        let mut edges_by_node_pair: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
 
-        for source_node in self.nodes.iter() {
-            for &destination_node_id in &source_node.outgoing_directed_edges {
-                edges.push(Edge {
-                    source_node_id: source_node.id,
-                    destination_node_id: destination_node_id,
-                    is_directed: true,
-                });
-            }
+        for (&edge_id, edge) in &self.edges {
+            let pair = (
+                edge.source_node_id.min(edge.destination_node_id),
+                edge.source_node_id.max(edge.destination_node_id),
+            );
+            edges_by_node_pair.entry(pair).or_default().push(edge_id);
         }
 
-        for source_node in self.nodes.iter() {
-            for &destination_node_id in &source_node.outgoing_undirected_edges {
+        let mut edges = Vec::new();
+
+        for ((first_node_id, second_node_id), edge_ids) in edges_by_node_pair {
+            let middle_lane = (edge_ids.len() - 1) as f32 / 2.0;
+
+            for (index, edge_id) in edge_ids.into_iter().enumerate() {
+                let edge = &self.edges[&edge_id];
+
+                // Lanes are measured from the lower to the higher node id so that edges running in
+                // opposite directions between the same nodes bend away from each other.
+                let lane = if first_node_id == second_node_id {
+                    index as f32
+                } else if edge.source_node_id == first_node_id {
+                    index as f32 - middle_lane
+                } else {
+                    middle_lane - index as f32
+                };
+
                 edges.push(Edge {
-                    source_node_id: source_node.id,
-                    destination_node_id: destination_node_id,
-                    is_directed: false,
+                    source_node_id: edge.source_node_id,
+                    destination_node_id: edge.destination_node_id,
+                    is_directed: edge.is_directed,
+                    weight: edge.weight,
+                    lane,
+                    label: edge.label.clone(),
+                    style: edge.style.unwrap_or(self.default_edge_style),
                 });
             }
         }
@@ -171,6 +714,113 @@ impl Graph {
     }
 }
 
+impl<N: Default, E> Graph<N, E> {
+    /// Adds a node, or returns the existing node if one already has this label. Use
+    /// [`Graph::try_add_node`] to be told about duplicates, or [`Graph::insert_node`] to add
+    /// several nodes with the same label.
+    pub fn add_node(&mut self, label: &str) -> NodeId {
+        match self.node_id(label) {
+            Some(id) => id,
+            None => self.insert_node(label),
+        }
+    }
+
+    pub fn try_add_node(&mut self, label: &str) -> Result<NodeId, GraphError> {
+        self.try_add_node_with_data(label, N::default())
+    }
+
+    /// Always adds a new node, even if another node already has this label. Labels are then only
+    /// display text and [`Graph::node_id`] keeps returning the first node with the label.
+    pub fn insert_node(&mut self, label: &str) -> NodeId {
+        self.insert_node_with_data(label, N::default())
+    }
+}
+
+impl<N, E: Default> Graph<N, E> {
+    /// Adds an edge from `from` to `to`, ignoring it if either node is missing. Use
+    /// [`Graph::try_add_directed_edge`] to be told why an edge was rejected.
+    pub fn add_directed_edge(&mut self, from: impl NodeKey, to: impl NodeKey) -> Option<EdgeId> {
+        self.add_directed_edge_with_data(from, to, E::default())
+    }
+
+    pub fn try_add_directed_edge(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+    ) -> Result<EdgeId, GraphError> {
+        self.try_add_directed_edge_with_data(from, to, E::default())
+    }
+
+    /// Adds an edge between `from` and `to`, ignoring it if either node is missing. Use
+    /// [`Graph::try_add_undirected_edge`] to be told why an edge was rejected.
+    pub fn add_undirected_edge(&mut self, from: impl NodeKey, to: impl NodeKey) -> Option<EdgeId> {
+        self.add_undirected_edge_with_data(from, to, E::default())
+    }
+
+    pub fn try_add_undirected_edge(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+    ) -> Result<EdgeId, GraphError> {
+        self.try_add_undirected_edge_with_data(from, to, E::default())
+    }
+
+    /// Adds an edge from `from` to `to` whose spring is stiffer and shorter the larger `weight`
+    /// is. Edges added without a weight have a weight of 1, and very large weights act like the
+    /// stiffest spring the simulation can step without overshooting.
+    pub fn add_weighted_edge(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        weight: f32,
+    ) -> Option<EdgeId> {
+        self.try_add_weighted_edge(from, to, weight).ok()
+    }
+
+    pub fn try_add_weighted_edge(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        weight: f32,
+    ) -> Result<EdgeId, GraphError> {
+        self.try_add_weighted(from, to, true, weight)
+    }
+
+    pub fn add_weighted_undirected_edge(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        weight: f32,
+    ) -> Option<EdgeId> {
+        self.try_add_weighted_undirected_edge(from, to, weight).ok()
+    }
+
+    pub fn try_add_weighted_undirected_edge(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        weight: f32,
+    ) -> Result<EdgeId, GraphError> {
+        self.try_add_weighted(from, to, false, weight)
+    }
+
+    fn try_add_weighted(
+        &mut self,
+        from: impl NodeKey,
+        to: impl NodeKey,
+        is_directed: bool,
+        weight: f32,
+    ) -> Result<EdgeId, GraphError> {
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(GraphError::InvalidWeight(weight));
+        }
+
+        let (from_id, to_id) = self.edge_endpoints(&from, &to)?;
+
+        Ok(self.connect(from_id, to_id, is_directed, E::default(), weight))
+    }
+}
+
 pub fn default_window_conf() -> Conf {
     Conf {
         window_title: "Emerge - Graph".to_owned(),
@@ -181,45 +831,72 @@ pub fn default_window_conf() -> Conf {
     }
 }
 
-pub async fn render_graph(graph: Graph) {
-    let mut world = spawn_initial(graph);
+pub async fn render_graph<N, E>(graph: Graph<N, E>) {
+    render_graph_with_config(graph, PhysicsConfig::default()).await
+}
+
+pub async fn render_graph_with_config<N, E>(graph: Graph<N, E>, config: PhysicsConfig) {
+    render_graph_with_selection_handler(graph, config, |_, _| {}).await
+}
+
+/// Renders the graph like [`render_graph_with_config`], calling `on_selection` whenever the user
+/// selects or deselects a node by clicking, shift-clicking or shift-dragging a box around nodes.
+/// The graph is passed alongside each event so the handler can look up the node's data.
+pub async fn render_graph_with_selection_handler<N, E>(
+    graph: Graph<N, E>,
+    config: PhysicsConfig,
+    mut on_selection: impl FnMut(SelectionEvent, &Graph<N, E>),
+) {
+    let mut graph_view = GraphView::new(graph).with_config(config);
 
     loop {
-        render(&mut world);
+        graph_view.handle_input();
+        graph_view.update(get_frame_time());
+        graph_view.draw(Rect::new(0.0, 0.0, screen_width(), screen_height()));
 
-        physics_update(&mut world);
+        for event in graph_view.drain_selection_events() {
+            on_selection(event, graph_view.graph());
+        }
 
-        renderer::view_port_update(&mut world);
+        next_frame().await
+    }
+}
+
+/// Renders the graph like [`render_graph_with_config`] while applying the updates sent on
+/// `updates`, so a producer on another thread can change the graph as it is shown. Updates are
+/// taken from the channel once a frame and those sent within the same frame are applied together.
+pub async fn render_graph_with_updates<N: Default, E: Default>(
+    graph: Graph<N, E>,
+    config: PhysicsConfig,
+    updates: Receiver<GraphUpdate>,
+) {
+    let mut graph_view = GraphView::new(graph).with_config(config);
+
+    loop {
+        graph_view.apply_updates(updates.try_iter());
+        graph_view.handle_input();
+        graph_view.update(get_frame_time());
+        graph_view.draw(Rect::new(0.0, 0.0, screen_width(), screen_height()));
+
+        // Nothing is listening for selection changes, so they are dropped rather than kept.
+        graph_view.drain_selection_events();
 
         next_frame().await
     }
 }
 
-fn spawn_initial(graph: Graph) -> World {
+pub(crate) fn spawn_graph<N, E>(graph: &Graph<N, E>, centre: Vec2) -> World {
     let mut world = World::new();
-    let view = ScrollableView::new();
-
-    world.spawn((view,));
 
     let all_edges = graph.get_all_edges();
     let node_count = graph.nodes.len();
 
-    for (index, node) in graph.nodes.into_iter().enumerate() {
+    for (index, node) in graph.nodes.values().enumerate() {
         let angle = 2.0 * PI * index as f32 / node_count as f32;
-        let x = screen_width() / 2.0 + 600.0 * angle.cos();
-        let y = screen_height() / 2.0 + 300.0 * angle.sin();
-
-        let renderable_node = (
-            node.id,
-            node.physics_data.velocity,
-            node.physics_data.force,
-            Position { x, y },
-            node.physics_data.size,
-            node.label,
-            BLACK,
-        );
+        let x = centre.x + 600.0 * angle.cos();
+        let y = centre.y + 300.0 * angle.sin();
 
-        world.spawn(renderable_node);
+        spawn_node(&mut world, node, Vec2::new(x, y));
     }
 
     for edge in all_edges {
@@ -229,9 +906,40 @@ fn spawn_initial(graph: Graph) -> World {
     world
 }
 
+pub(crate) fn spawn_node<N>(world: &mut World, node: &Node<N>, position: Vec2) -> Entity {
+    let renderable_node = (
+        node.id,
+        node.physics_data.velocity.clone(),
+        node.physics_data.force.clone(),
+        Position {
+            x: position.x,
+            y: position.y,
+        },
+        Size {
+            radius: node.style.radius,
+        },
+        node.label.clone(),
+        node.style,
+        node.metadata.clone(),
+    );
+
+    let entity = world.spawn(renderable_node);
+
+    if node.is_pinned {
+        world.insert_one(entity, Pinned).unwrap();
+    }
+
+    if let Some(cluster) = &node.cluster {
+        world.insert_one(entity, Cluster(cluster.clone())).unwrap();
+    }
+
+    entity
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::style::{ArrowHead, LinePattern, NodeShape};
 
     #[test]
     fn should_add_node() {
@@ -251,12 +959,379 @@ mod tests {
 
         graph.add_node("A");
         graph.add_node("B");
-        graph.add_directed_edge("A", "B");
+        let edge = graph.add_directed_edge("A", "B").unwrap();
 
         let node_a = graph.get_node_by_name("A").unwrap();
         let node_b = graph.get_node_by_name("B").unwrap();
 
-        assert!(node_a.outgoing_directed_edges.contains(&node_b.id));
-        assert!(node_b.incoming_directed_edges.contains(&node_a.id));
+        assert!(node_a.outgoing_directed_edges.contains(&edge.0));
+        assert!(node_b.incoming_directed_edges.contains(&edge.0));
+        assert_eq!(
+            graph.edge_nodes(edge),
+            Some((NodeId(node_a.id), NodeId(node_b.id)))
+        );
+    }
+
+    #[test]
+    fn should_reject_duplicate_labels() {
+        let mut graph = Graph::new();
+
+        assert_eq!(graph.try_add_node("A"), Ok(NodeId(0)));
+        assert_eq!(
+            graph.try_add_node("A"),
+            Err(GraphError::DuplicateLabel("A".to_string()))
+        );
+
+        graph.add_node("A");
+
+        assert_eq!(graph.nodes.len(), 1);
+    }
+
+    #[test]
+    fn should_reject_edges_to_missing_nodes() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+
+        assert_eq!(
+            graph.try_add_directed_edge("A", "B"),
+            Err(GraphError::NodeNotFound("B".to_string()))
+        );
+        assert_eq!(
+            graph.try_add_undirected_edge("C", "A"),
+            Err(GraphError::NodeNotFound("C".to_string()))
+        );
+        assert!(graph.get_all_edges().is_empty());
+    }
+
+    #[test]
+    fn should_keep_parallel_edges_and_self_loops() {
+        let mut graph: Graph<(), &str> = Graph::default();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        let reads = graph.add_directed_edge_with_data(a, b, "reads").unwrap();
+        let writes = graph.add_directed_edge_with_data(a, b, "writes").unwrap();
+        let retries = graph.add_directed_edge_with_data(a, a, "retries").unwrap();
+
+        assert_ne!(reads, writes);
+        assert_eq!(graph.edges_between(a, b), vec![reads, writes]);
+        assert_eq!(graph.edge_data(writes), Some(&"writes"));
+        assert_eq!(graph.edge_nodes(retries), Some((a, a)));
+        assert_eq!(graph.get_all_edges().len(), 3);
+
+        assert!(graph.remove_edge_by_id(reads));
+
+        assert_eq!(graph.edges_between(a, b), vec![writes]);
+
+        graph.remove_node(a);
+
+        assert!(graph.edges.is_empty());
+        assert!(graph
+            .get_node_by_name("B")
+            .unwrap()
+            .incoming_directed_edges
+            .is_empty());
+    }
+
+    #[test]
+    fn should_keep_node_metadata_in_order() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+
+        assert!(graph.set_node_metadata(a, "population", "2.8m"));
+        assert!(graph.set_node_metadata(a, "capital", "Tirana"));
+        assert!(graph.set_node_metadata(a, "population", "2.7m"));
+        assert!(!graph.set_node_metadata("B", "capital", "Abuja"));
+
+        assert_eq!(graph.node_metadata(a, "population"), Some("2.7m"));
+        assert_eq!(
+            graph.nodes[&a.0].metadata.0,
+            vec![
+                ("population".to_string(), "2.7m".to_string()),
+                ("capital".to_string(), "Tirana".to_string())
+            ]
+        );
+
+        assert_eq!(
+            graph.remove_node_metadata(a, "capital"),
+            Some("Tirana".to_string())
+        );
+        assert_eq!(graph.node_metadata(a, "capital"), None);
+    }
+
+    #[test]
+    fn should_style_nodes() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let style = NodeStyle {
+            radius: 30.0,
+            shape: NodeShape::Diamond,
+            ..NodeStyle::default()
+        };
+
+        assert_eq!(graph.node_style(a), Some(&NodeStyle::default()));
+        assert!(graph.set_node_style(a, style));
+        assert_eq!(graph.node_style("A"), Some(&style));
+        assert!(!graph.set_node_style("B", style));
+
+        let mut world = spawn_graph(&graph, Vec2::ZERO);
+        let (_, size) = world.query_mut::<&Size>().into_iter().next().unwrap();
+
+        assert_eq!(size.radius, 30.0);
+    }
+
+    #[test]
+    fn should_style_edges_with_graph_default() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        let plain = graph.add_directed_edge(a, b).unwrap();
+        let styled = graph.add_directed_edge(b, a).unwrap();
+
+        let dashed = EdgeStyle {
+            pattern: LinePattern::Dashed,
+            ..EdgeStyle::default()
+        };
+        let filled = EdgeStyle {
+            arrowhead: ArrowHead::Filled,
+            both_ends: true,
+            ..EdgeStyle::default()
+        };
+
+        assert!(graph.set_edge_style(styled, Some(filled)));
+        graph.set_default_edge_style(dashed);
+
+        assert_eq!(graph.edge_style(plain), Some(&dashed));
+        assert_eq!(graph.edge_style(styled), Some(&filled));
+
+        graph.set_edge_style(styled, None);
+
+        assert_eq!(graph.edge_style(styled), Some(&dashed));
+        assert!(graph
+            .get_all_edges()
+            .iter()
+            .all(|edge| edge.style == dashed));
+    }
+
+    #[test]
+    fn should_label_edges() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        let edge = graph.add_directed_edge(a, b).unwrap();
+
+        assert_eq!(graph.edge_label(edge), None);
+        assert!(graph.set_edge_label(edge, Some("depends on")));
+        assert_eq!(graph.edge_label(edge), Some("depends on"));
+        assert_eq!(
+            graph.get_all_edges()[0].label.as_deref(),
+            Some("depends on")
+        );
+
+        graph.set_edge_label(edge, None);
+        graph.remove_edge_by_id(edge);
+
+        assert_eq!(graph.edge_label(edge), None);
+        assert!(!graph.set_edge_label(edge, Some("gone")));
+    }
+
+    #[test]
+    fn should_spread_parallel_edges_across_lanes() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph.add_directed_edge(a, b);
+        graph.add_directed_edge(b, a);
+        graph.add_undirected_edge(a, b);
+        graph.add_directed_edge(b, b);
+        graph.add_directed_edge(b, b);
+
+        let lanes: Vec<f32> = graph.get_all_edges().iter().map(|edge| edge.lane).collect();
+
+        assert_eq!(lanes, vec![-1.0, 0.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn should_look_up_nodes_by_id_and_label() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+
+        assert_eq!(graph.add_node("A"), a);
+        assert_eq!(graph.node_id("B"), Some(b));
+        assert_eq!(graph.label(a), Some("A"));
+        assert_eq!(graph.node_id("C"), None);
+    }
+
+    #[test]
+    fn should_add_edges_by_id() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph.remove_node(b);
+
+        assert_eq!(
+            graph.try_add_directed_edge(a, b),
+            Err(GraphError::UnknownNodeId(b))
+        );
+
+        let c = graph.add_node("C");
+        let edge = graph.add_directed_edge(a, c).unwrap();
+
+        assert_eq!(graph.edge_nodes(edge), Some((a, c)));
+    }
+
+    #[test]
+    fn should_allow_non_unique_labels() {
+        let mut graph = Graph::new();
+
+        let first = graph.insert_node("Service");
+        let second = graph.insert_node("Service");
+
+        graph.add_undirected_edge(first, second);
+
+        assert_ne!(first, second);
+        assert_eq!(graph.label(second), Some("Service"));
+        assert_eq!(graph.node_id("Service"), Some(first));
+        assert_eq!(graph.get_all_edges().len(), 1);
+    }
+
+    #[test]
+    fn should_store_node_and_edge_data() {
+        let mut graph: Graph<&str, u32> = Graph::default();
+
+        let a = graph.add_node_with_data("A", "payments");
+        let b = graph.add_node("B");
+        graph.add_directed_edge_with_data(a, b, 250);
+        graph.add_undirected_edge(b, a);
+
+        *graph.node_data_mut(b).unwrap() = "ledger";
+
+        assert_eq!(graph.node_data("A"), Some(&"payments"));
+        assert_eq!(graph.node_data(b), Some(&"ledger"));
+        assert_eq!(graph.directed_edge_data(a, b), Some(&250));
+        assert_eq!(graph.directed_edge_data(b, a), None);
+        assert_eq!(graph.undirected_edge_data(a, b), Some(&0));
+
+        graph.remove_node(b);
+
+        assert_eq!(graph.directed_edge_data(a, "B"), None);
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn should_keep_existing_node_data_when_adding_a_duplicate_label() {
+        let mut graph: Graph<&str> = Graph::default();
+
+        let a = graph.add_node_with_data("A", "payments");
+
+        assert_eq!(graph.add_node_with_data("A", "ledger"), a);
+        assert_eq!(graph.node_data(a), Some(&"payments"));
+        assert_eq!(graph.nodes.len(), 1);
+    }
+
+    #[test]
+    fn should_store_edge_weights() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        let c = graph.add_node("C");
+        graph.add_weighted_edge(a, b, 4.0);
+        graph.add_undirected_edge(b, c);
+
+        assert_eq!(
+            graph.try_add_weighted_undirected_edge(a, c, 0.0),
+            Err(GraphError::InvalidWeight(0.0))
+        );
+
+        let mut weights: Vec<f32> = graph.get_all_edges().iter().map(|e| e.weight).collect();
+        weights.sort_by(f32::total_cmp);
+
+        assert_eq!(weights, vec![1.0, 4.0]);
+    }
+
+    #[test]
+    fn should_remove_node_and_its_edges() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_node("C");
+        graph.add_directed_edge("A", "B");
+        graph.add_undirected_edge("B", "C");
+
+        assert!(graph.remove_node("B"));
+        assert!(!graph.remove_node("B"));
+
+        let node_a = graph.get_node_by_name("A").unwrap();
+        let node_c = graph.get_node_by_name("C").unwrap();
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(node_a.outgoing_directed_edges.is_empty());
+        assert!(node_c.incoming_undirected_edges.is_empty());
+        assert!(graph.get_all_edges().is_empty());
+    }
+
+    #[test]
+    fn should_look_up_a_remaining_node_after_removing_one_with_a_shared_label() {
+        let mut graph = Graph::new();
+
+        let first = graph.insert_node("A");
+        let second = graph.insert_node("A");
+
+        assert_eq!(graph.node_id("A"), Some(first));
+        assert!(graph.remove_node(first));
+        assert_eq!(graph.node_id("A"), Some(second));
+        assert!(graph.remove_node("A"));
+        assert_eq!(graph.node_id("A"), None);
+    }
+
+    #[test]
+    fn should_not_reuse_ids_of_removed_nodes() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.remove_node("A");
+        graph.add_node("C");
+
+        assert_eq!(graph.get_node_by_name("B").unwrap().id, 1);
+        assert_eq!(graph.get_node_by_name("C").unwrap().id, 2);
+    }
+
+    #[test]
+    fn should_remove_edges() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_node("C");
+        graph.add_directed_edge("A", "B");
+        graph.add_directed_edge("B", "A");
+        graph.add_undirected_edge("C", "A");
+
+        assert!(graph.remove_edge("A", "B"));
+        assert!(!graph.remove_edge("A", "B"));
+        assert!(graph.remove_edge("A", "C"));
+
+        let edges = graph.get_all_edges();
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].source_node_id, 1);
+        assert_eq!(edges[0].destination_node_id, 0);
+
+        graph.clear_edges();
+
+        assert!(graph.get_all_edges().is_empty());
+        assert_eq!(graph.nodes.len(), 3);
     }
 }
@@ -0,0 +1,416 @@
+use crate::graph::{spawn_graph, Graph, NodeId, NodeKey, Pinned, Position};
+use crate::physics::{physics_update, PhysicsConfig, StepStats};
+use hecs::{Entity, World};
+use macroquad::math::Vec2;
+use std::collections::HashMap;
+
+/// Runs the force simulation for a [`Graph`] without opening a window.
+pub struct Layout<N = (), E = ()> {
+    graph: Graph<N, E>,
+    world: World,
+    config: PhysicsConfig,
+    last_step: Option<StepStats>,
+}
+
+impl<N, E> Layout<N, E> {
+    pub fn new(graph: Graph<N, E>) -> Self {
+        Self {
+            world: spawn_graph(&graph, Vec2::ZERO),
+            graph,
+            config: PhysicsConfig::default(),
+            last_step: None,
+        }
+    }
+
+    /// The graph being laid out, giving access to the data attached to its nodes and edges.
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    pub fn with_config(mut self, config: PhysicsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &PhysicsConfig {
+        &self.config
+    }
+
+    /// Changes the physics between steps. The layout is no longer considered settled afterwards,
+    /// so [`Layout::run_until_settled`] will pick up the new parameters.
+    pub fn set_config(&mut self, config: PhysicsConfig) {
+        self.config = config;
+        self.last_step = None;
+    }
+
+    pub fn step(&mut self) {
+        self.last_step = Some(physics_update(&mut self.world, &self.config));
+    }
+
+    pub fn run(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            self.step();
+        }
+    }
+
+    /// Steps the simulation until it settles or `max_iterations` is reached, returning the number
+    /// of steps taken.
+    pub fn run_until_settled(&mut self, max_iterations: usize) -> usize {
+        for iteration in 0..max_iterations {
+            self.step();
+
+            if self.is_settled() {
+                return iteration + 1;
+            }
+        }
+
+        max_iterations
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.last_step
+            .is_some_and(|stats| stats.is_settled(&self.config))
+    }
+
+    pub fn kinetic_energy(&self) -> f32 {
+        self.last_step.map_or(0.0, |stats| stats.kinetic_energy)
+    }
+
+    pub fn max_displacement(&self) -> f32 {
+        self.last_step.map_or(0.0, |stats| stats.max_displacement)
+    }
+
+    pub fn position(&self, label: &str) -> Option<Vec2> {
+        self.world
+            .query::<(&Position, &String)>()
+            .iter()
+            .find(|(_, (_, node_label))| node_label.as_str() == label)
+            .map(|(_, (position, _))| Vec2::new(position.x, position.y))
+    }
+
+    pub fn node_position(&self, id: NodeId) -> Option<Vec2> {
+        self.world
+            .query::<(&Position, &usize)>()
+            .iter()
+            .find(|(_, (_, &node_id))| node_id == id.0)
+            .map(|(_, (position, _))| Vec2::new(position.x, position.y))
+    }
+
+    /// Moves a node, for example to place it before pinning it there.
+    pub fn set_node_position(&mut self, id: NodeId, position: Vec2) -> bool {
+        let Some(entity) = self.node_entity(id) else {
+            return false;
+        };
+
+        *self.world.get::<&mut Position>(entity).unwrap() = Position {
+            x: position.x,
+            y: position.y,
+        };
+        self.last_step = None;
+        true
+    }
+
+    /// Holds a node in place for the following steps, or releases it. Returns whether the node
+    /// exists.
+    pub fn set_pinned(&mut self, node: impl NodeKey, is_pinned: bool) -> bool {
+        let Some(entity) = node
+            .resolve(&self.graph)
+            .ok()
+            .and_then(|id| self.node_entity(id))
+        else {
+            return false;
+        };
+
+        self.graph.set_pinned(&node, is_pinned);
+
+        if is_pinned {
+            self.world.insert_one(entity, Pinned).unwrap();
+        } else {
+            let _ = self.world.remove_one::<Pinned>(entity);
+        }
+
+        self.last_step = None;
+        true
+    }
+
+    pub fn is_pinned(&self, node: impl NodeKey) -> bool {
+        self.graph.is_pinned(node)
+    }
+
+    fn node_entity(&self, id: NodeId) -> Option<Entity> {
+        self.world
+            .query::<&usize>()
+            .iter()
+            .find(|(_, &node_id)| node_id == id.0)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Every node's position, keyed by id so that nodes sharing a label are all included.
+    pub fn positions(&self) -> HashMap<NodeId, Vec2> {
+        self.world
+            .query::<(&Position, &usize)>()
+            .iter()
+            .map(|(_, (position, &id))| (NodeId(id), Vec2::new(position.x, position.y)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_return_a_position_for_every_node() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_node("C");
+        graph.add_directed_edge("A", "B");
+
+        let mut layout = Layout::new(graph);
+        layout.run(10);
+
+        let positions = layout.positions();
+
+        assert_eq!(positions.len(), 3);
+        assert!(positions.values().all(|position| position.is_finite()));
+        assert_eq!(layout.node_position(NodeId(0)), layout.position("A"));
+    }
+
+    #[test]
+    fn should_return_a_position_for_nodes_sharing_a_label() {
+        let mut graph = Graph::new();
+
+        let first = graph.insert_node("A");
+        let second = graph.insert_node("A");
+
+        let layout = Layout::new(graph);
+        let positions = layout.positions();
+
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions.get(&first).copied(), layout.node_position(first));
+        assert_eq!(
+            positions.get(&second).copied(),
+            layout.node_position(second)
+        );
+    }
+
+    #[test]
+    fn should_keep_node_data_alongside_positions() {
+        let mut graph: Graph<u32, &str> = Graph::default();
+
+        let a = graph.add_node_with_data("A", 7);
+        let b = graph.add_node_with_data("B", 9);
+        graph.add_directed_edge_with_data(a, b, "calls");
+
+        let mut layout = Layout::new(graph);
+        layout.run(10);
+
+        assert_eq!(layout.graph().node_data(a), Some(&7));
+        assert_eq!(layout.graph().directed_edge_data(a, b), Some(&"calls"));
+        assert!(layout.node_position(b).is_some());
+    }
+
+    #[test]
+    fn should_pull_connected_nodes_towards_resting_length() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_directed_edge("A", "B");
+
+        let mut layout = Layout::new(graph);
+        let initial_distance = layout
+            .position("A")
+            .unwrap()
+            .distance(layout.position("B").unwrap());
+
+        layout.run(100);
+
+        let final_distance = layout
+            .position("A")
+            .unwrap()
+            .distance(layout.position("B").unwrap());
+
+        assert!(final_distance < initial_distance);
+    }
+
+    #[test]
+    fn should_settle_a_small_graph() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_node("C");
+        graph.add_directed_edge("A", "B");
+        graph.add_directed_edge("B", "C");
+
+        let mut layout = Layout::new(graph);
+
+        assert!(!layout.is_settled());
+
+        let iterations = layout.run_until_settled(10_000);
+
+        assert!(iterations < 10_000);
+        assert!(layout.is_settled());
+    }
+
+    #[test]
+    fn should_settle_with_a_very_heavy_edge() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph
+            .try_add_weighted_undirected_edge(a, b, 1000.0)
+            .unwrap();
+
+        let mut layout = Layout::new(graph);
+        let iterations = layout.run_until_settled(10_000);
+        let position = layout.node_position(a).unwrap();
+
+        assert!(iterations < 10_000);
+        assert!(position.is_finite());
+        assert!(position.distance(layout.node_position(b).unwrap()) < 100.0);
+    }
+
+    fn settle_star(leaf_count: usize, weight: f32) -> (usize, Layout) {
+        let mut graph = Graph::new();
+
+        let hub = graph.add_node("hub");
+        for leaf in 0..leaf_count {
+            let leaf = graph.add_node(&leaf.to_string());
+            graph
+                .try_add_weighted_undirected_edge(hub, leaf, weight)
+                .unwrap();
+        }
+
+        let mut layout = Layout::new(graph);
+        let iterations = layout.run_until_settled(10_000);
+
+        (iterations, layout)
+    }
+
+    #[test]
+    fn should_settle_a_hub_with_a_few_heavy_edges() {
+        let (iterations, _) = settle_star(3, 25.0);
+
+        assert!(iterations < 10_000);
+    }
+
+    #[test]
+    fn should_settle_a_hub_with_many_heavy_edges() {
+        let (iterations, _) = settle_star(10, 10.0);
+
+        assert!(iterations < 10_000);
+    }
+
+    #[test]
+    fn should_settle_a_hub_with_very_heavy_edges() {
+        for leaf_count in [4, 40] {
+            let (iterations, layout) = settle_star(leaf_count, 1000.0);
+
+            assert!(iterations < 10_000, "{} leaves", leaf_count);
+            assert!(layout.positions().values().all(|p| p.is_finite()));
+        }
+    }
+
+    #[test]
+    fn should_settle_with_many_parallel_heavy_edges() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        for _ in 0..30 {
+            graph
+                .try_add_weighted_undirected_edge(a, b, 1000.0)
+                .unwrap();
+        }
+
+        let mut layout = Layout::new(graph);
+        let iterations = layout.run_until_settled(10_000);
+
+        assert!(iterations < 10_000);
+        assert!(layout.node_position(a).unwrap().is_finite());
+    }
+
+    #[test]
+    fn should_keep_large_nodes_from_overlapping() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph.add_undirected_edge(a, b);
+
+        for node in [a, b] {
+            graph.node_style_mut(node).unwrap().radius = 60.0;
+        }
+
+        let mut layout = Layout::new(graph);
+        layout.run_until_settled(10_000);
+
+        let distance = layout
+            .node_position(a)
+            .unwrap()
+            .distance(layout.node_position(b).unwrap());
+
+        assert!(distance > 110.0);
+    }
+
+    #[test]
+    fn should_hold_pinned_nodes_in_place() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph.add_directed_edge(a, b);
+        graph.set_pinned(a, true);
+
+        let mut layout = Layout::new(graph);
+        let initial_position = layout.node_position(a).unwrap();
+
+        layout.set_pinned(b, true);
+        layout.set_node_position(b, Vec2::new(1000.0, 0.0));
+        layout.run(100);
+
+        assert_eq!(layout.node_position(a), Some(initial_position));
+        assert_eq!(layout.node_position(b), Some(Vec2::new(1000.0, 0.0)));
+
+        layout.set_pinned(b, false);
+        layout.run(100);
+
+        assert_eq!(layout.node_position(a), Some(initial_position));
+        assert!(layout.node_position(b).unwrap().distance(initial_position) < 500.0);
+        assert!(layout.is_pinned(a));
+        assert!(!layout.is_pinned(b));
+    }
+
+    #[test]
+    fn should_resume_after_config_change() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_directed_edge("A", "B");
+
+        let mut layout = Layout::new(graph);
+        layout.run_until_settled(10_000);
+
+        layout.set_config(PhysicsConfig {
+            spring_resting_length: 300.0,
+            ..PhysicsConfig::default()
+        });
+
+        assert!(!layout.is_settled());
+
+        layout.run_until_settled(10_000);
+
+        let distance = layout
+            .position("A")
+            .unwrap()
+            .distance(layout.position("B").unwrap());
+
+        assert!((distance - 300.0).abs() < 5.0);
+    }
+}
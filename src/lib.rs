@@ -1,3 +1,8 @@
+pub mod dot;
 pub mod graph;
-mod physics;
+pub mod layout;
+pub mod physics;
+mod quadtree;
 mod renderer;
+pub mod style;
+pub mod view;
@@ -1,127 +1,267 @@
-use crate::graph::{Edge, Force, Position, Velocity};
-use hecs::World;
+use crate::graph::{Edge, Force, Frozen, Pinned, Position, Size, Velocity};
+use crate::quadtree::QuadTree;
+use hecs::{Entity, World};
+use macroquad::math::Vec2;
 use std::collections::HashMap;
 
-const TIME_STEP: f32 = 0.1f32;
-const SPRING_CONSTANT: f32 = 1f32;
-const SPRING_RESTING_LENGTH: f32 = 100f32;
-const ELECTROSTATIC_CONSTANT: f32 = 20000f32;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsConfig {
+    pub time_step: f32,
+    pub spring_constant: f32,
+    pub spring_resting_length: f32,
+    pub electrostatic_constant: f32,
+    /// Barnes–Hut accuracy for repulsion. Groups of nodes whose extent divided by their distance
+    /// is below this are treated as one body; zero computes every pair exactly, as is always done
+    /// for small graphs.
+    pub theta: f32,
+    /// Fraction of a node's velocity lost on every step.
+    pub damping: f32,
+    pub max_speed: f32,
+    /// How hard overlapping nodes are pushed apart, per unit of overlap between their radii.
+    pub collision_strength: f32,
+    /// The simulation counts as settled once no node moves further than this in a single step.
+    pub settle_threshold: f32,
+    /// The simulation also only counts as settled once the average kinetic energy of the moving
+    /// nodes is below this, so a layout that is still drifting as a whole keeps running.
+    pub settle_energy: f32,
+}
 
-pub fn physics_update(world: &mut World) {
-    let node_data = node_positions_by_id(world);
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            time_step: 0.1,
+            spring_constant: 1.0,
+            spring_resting_length: 100.0,
+            electrostatic_constant: 20000.0,
+            theta: 0.8,
+            damping: 0.2,
+            max_speed: 100.0,
+            collision_strength: 20.0,
+            settle_threshold: 0.05,
+            settle_energy: 0.01,
+        }
+    }
+}
 
-    apply_attractive_forces(world, &node_data);
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct StepStats {
+    pub(crate) kinetic_energy: f32,
+    pub(crate) max_displacement: f32,
+    pub(crate) moving_nodes: usize,
+}
 
-    apply_repulsive_forces(world, &node_data);
+impl StepStats {
+    pub(crate) fn is_settled(&self, config: &PhysicsConfig) -> bool {
+        let average_energy = self.kinetic_energy / self.moving_nodes.max(1) as f32;
 
-    simulate_time_step(world);
+        self.max_displacement < config.settle_threshold && average_energy < config.settle_energy
+    }
+}
 
-    clear_all_forces(world)
+// Every node's entity, id and position for the current step, stored densely so forces can be
+// accumulated into a buffer by index rather than by searching the world for each node.
+struct NodeBuffer {
+    entities: Vec<Entity>,
+    positions: Vec<Position>,
+    radii: Vec<f32>,
+    index_by_node_id: HashMap<usize, usize>,
 }
 
-fn apply_repulsive_forces(world: &mut World, node_data: &HashMap<usize, Position>) {
-    for (&first_node_id, first_node_position) in node_data {
-        for (&second_node_id, second_node_position) in node_data {
-            if first_node_id != second_node_id
-                && is_in_range(first_node_position, second_node_position)
-            {
-                let force = calculate_electrostatic_forces_between_nodes(
-                    &first_node_position,
-                    &second_node_position,
-                );
-                apply_force_to_node(
-                    world,
-                    first_node_id,
-                    Force {
-                        x: -force.x,
-                        y: -force.y,
-                    },
-                );
-            }
+impl NodeBuffer {
+    fn collect(world: &mut World) -> Self {
+        let mut entities = Vec::new();
+        let mut positions = Vec::new();
+        let mut radii = Vec::new();
+        let mut index_by_node_id = HashMap::new();
+
+        for (entity, (position, size, &node_id)) in world.query_mut::<(&Position, &Size, &usize)>()
+        {
+            index_by_node_id.insert(node_id, entities.len());
+            entities.push(entity);
+            positions.push(position.clone());
+            radii.push(size.radius);
+        }
+
+        Self {
+            entities,
+            positions,
+            radii,
+            index_by_node_id,
         }
     }
 }
 
-fn clear_all_forces(world: &mut World) {
-    let node_data: HashMap<usize, Position> = node_positions_by_id(world);
+pub(crate) fn physics_update(world: &mut World, config: &PhysicsConfig) -> StepStats {
+    let nodes = NodeBuffer::collect(world);
+    let mut forces: Vec<Force> = nodes
+        .positions
+        .iter()
+        .map(|_| Force { x: 0.0, y: 0.0 })
+        .collect();
+
+    accumulate_attractive_forces(world, config, &nodes, &mut forces);
+
+    accumulate_repulsive_forces(config, &nodes, &mut forces);
+
+    accumulate_collision_forces(config, &nodes, &mut forces);
+
+    simulate_time_step(world, config, &nodes, forces)
+}
+
+// Below this many nodes every pair is compared exactly, which is cheap at that size. The Barnes–Hut
+// approximation is not symmetric, so it leaves a small net force on tightly bound groups of nodes
+// that can keep them from ever settling.
+const MAX_NODES_FOR_EXACT_REPULSION: usize = 100;
 
-    for (&id, _) in &node_data {
-        match world
-            .query::<(&mut Force, &usize)>()
-            .iter()
-            .find(|(_entity, (_force, &node_id))| id == node_id)
-        {
-            Some((_found_node, (force, _node_id))) => {
-                force.x = 0.0;
-                force.y = 0.0;
-            }
-            _ => {
-                panic!("Could not find the node to apply a force to, this really should not happen")
-            }
-        }
+fn accumulate_repulsive_forces(config: &PhysicsConfig, nodes: &NodeBuffer, forces: &mut [Force]) {
+    let theta = if nodes.positions.len() <= MAX_NODES_FOR_EXACT_REPULSION {
+        0.0
+    } else {
+        config.theta
+    };
+    let positions: Vec<Vec2> = nodes
+        .positions
+        .iter()
+        .map(|position| Vec2::new(position.x, position.y))
+        .collect();
+    let quad_tree = QuadTree::new(&positions);
+
+    for (index, node_position) in nodes.positions.iter().enumerate() {
+        let force = &mut forces[index];
+
+        quad_tree.for_each_influence(index, positions[index], theta, |other, mass| {
+            let repulsive_force = calculate_electrostatic_forces_between_nodes(
+                config,
+                node_position,
+                &Position {
+                    x: other.x,
+                    y: other.y,
+                },
+            );
+
+            force.x -= repulsive_force.x * mass;
+            force.y -= repulsive_force.y * mass;
+        });
     }
 }
 
-fn apply_attractive_forces(world: &mut World, node_data: &HashMap<usize, Position>) {
-    let edge_data = edge_by_id(world);
+// Nodes are bucketed into a grid with cells as wide as the largest node, so only nodes in
+// neighbouring cells can overlap and need to be compared.
+fn accumulate_collision_forces(config: &PhysicsConfig, nodes: &NodeBuffer, forces: &mut [Force]) {
+    let cell_size = 2.0
+        * nodes
+            .radii
+            .iter()
+            .fold(0.0_f32, |max, &radius| max.max(radius));
+
+    if cell_size <= 0.0 || config.collision_strength == 0.0 {
+        return;
+    }
+
+    let cell_of = |position: &Position| {
+        (
+            (position.x / cell_size).floor() as i64,
+            (position.y / cell_size).floor() as i64,
+        )
+    };
 
-    for (_, edge) in edge_data {
-        let edge_source_node_id = edge.source_node_id;
-        let edge_destination_node_id = edge.destination_node_id;
+    let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
 
-        if node_data.contains_key(&edge_source_node_id)
-            && node_data.contains_key(&edge_destination_node_id)
+    for (index, position) in nodes.positions.iter().enumerate() {
+        grid.entry(cell_of(position)).or_default().push(index);
+    }
+
+    for (index, position) in nodes.positions.iter().enumerate() {
+        let (cell_x, cell_y) = cell_of(position);
+
+        for neighbour_cell in
+            (cell_x - 1..=cell_x + 1).flat_map(|x| (cell_y - 1..=cell_y + 1).map(move |y| (x, y)))
         {
-            match (
-                node_data.get(&edge_source_node_id),
-                node_data.get(&edge_destination_node_id),
-            ) {
-                (Some(source_node_position), Some(destination_node_position)) => {
-                    let attractive_force_between_nodes = calculate_spring_forces_between_nodes(
-                        destination_node_position,
-                        source_node_position,
-                    );
-
-                    apply_force_to_node(
-                        world,
-                        edge_source_node_id,
-                        Force {
-                            x: attractive_force_between_nodes.x,
-                            y: attractive_force_between_nodes.y,
-                        },
-                    );
-                    apply_force_to_node(
-                        world,
-                        edge_destination_node_id,
-                        Force {
-                            x: -attractive_force_between_nodes.x,
-                            y: -attractive_force_between_nodes.y,
-                        },
-                    );
+            for &other_index in grid.get(&neighbour_cell).into_iter().flatten() {
+                if other_index <= index {
+                    continue;
                 }
-                _ => {}
+
+                let collision_force = calculate_collision_forces_between_nodes(
+                    config,
+                    position,
+                    nodes.radii[index],
+                    &nodes.positions[other_index],
+                    nodes.radii[other_index],
+                );
+
+                forces[index].x -= collision_force.x;
+                forces[index].y -= collision_force.y;
+                forces[other_index].x += collision_force.x;
+                forces[other_index].y += collision_force.y;
             }
-        };
+        }
     }
 }
 
-fn apply_force_to_node(world: &mut World, node_id_to_match: usize, force_to_apply: Force) {
-    match world
-        .query::<(&mut Force, &usize)>()
+fn accumulate_attractive_forces(
+    world: &World,
+    config: &PhysicsConfig,
+    nodes: &NodeBuffer,
+    forces: &mut [Force],
+) {
+    let springs: Vec<(usize, usize, f32)> = world
+        .query::<&Edge>()
         .iter()
-        .find(|(_entity, (_force, &node_id))| node_id_to_match == node_id)
-    {
-        Some((_found_node, (force, _node_id))) => {
-            force.x += force_to_apply.x;
-            force.y += force_to_apply.y;
-        }
-        _ => {
-            panic!("Could not find the node to apply a force to, this really should not happen")
-        }
+        .filter_map(|(_, edge)| {
+            let source_index = *nodes.index_by_node_id.get(&edge.source_node_id)?;
+            let destination_index = *nodes.index_by_node_id.get(&edge.destination_node_id)?;
+
+            (source_index != destination_index).then_some((
+                source_index,
+                destination_index,
+                spring_weight(config, edge.weight),
+            ))
+        })
+        .collect();
+
+    // A node held by several springs is as stiff as all of them together, so the bound applies to
+    // each node's total and every spring is weakened by whichever of its two ends is stiffest.
+    let mut stiffness = vec![0.0; forces.len()];
+    for &(source_index, destination_index, weight) in &springs {
+        stiffness[source_index] += config.spring_constant * weight;
+        stiffness[destination_index] += config.spring_constant * weight;
+    }
+    let max_stiffness = MAX_SPRING_STIFFNESS_PER_STEP / config.time_step.powi(2);
+
+    for (source_index, destination_index, weight) in springs {
+        let scale =
+            (max_stiffness / stiffness[source_index].max(stiffness[destination_index])).min(1.0);
+
+        let attractive_force_between_nodes = calculate_spring_forces_between_nodes(
+            config,
+            weight,
+            &nodes.positions[destination_index],
+            &nodes.positions[source_index],
+        );
+
+        forces[source_index].x += attractive_force_between_nodes.x * scale;
+        forces[source_index].y += attractive_force_between_nodes.y * scale;
+        forces[destination_index].x -= attractive_force_between_nodes.x * scale;
+        forces[destination_index].y -= attractive_force_between_nodes.y * scale;
     }
 }
 
+// The semi-implicit Euler step overshoots and diverges once a node's stiffness times the squared
+// time step grows past about 4. Springs are weakened to stay well below that, leaving room for the
+// repulsion and collision forces acting on the same nodes.
+const MAX_SPRING_STIFFNESS_PER_STEP: f32 = 0.25;
+
+// Heavier edges pull nodes closer, but no edge is treated as heavier than a single spring that
+// already reaches the stiffness bound, so the resting length never shrinks into the nodes.
+fn spring_weight(config: &PhysicsConfig, weight: f32) -> f32 {
+    weight.min(MAX_SPRING_STIFFNESS_PER_STEP / (config.spring_constant * config.time_step.powi(2)))
+}
+
+// Heavier edges get proportionally stiffer springs, and shorter ones so related nodes cluster.
 fn calculate_spring_forces_between_nodes(
+    config: &PhysicsConfig,
+    weight: f32,
     source_node_position: &Position,
     destination_node_position: &Position,
 ) -> Force {
@@ -129,71 +269,229 @@ fn calculate_spring_forces_between_nodes(
     let dy = destination_node_position.y - source_node_position.y;
 
     let current_length = (dx * dx + dy * dy).sqrt();
-    let displacement_from_rest = current_length - SPRING_RESTING_LENGTH;
+
+    if current_length == 0.0 {
+        return Force { x: 0.0, y: 0.0 };
+    }
+    let spring_constant = config.spring_constant * weight;
+    let resting_length = config.spring_resting_length / weight.sqrt();
+    let displacement_from_rest = current_length - resting_length;
 
     Force {
-        x: -SPRING_CONSTANT * displacement_from_rest * (dx / current_length),
-        y: -SPRING_CONSTANT * displacement_from_rest * (dy / current_length),
+        x: -spring_constant * displacement_from_rest * (dx / current_length),
+        y: -spring_constant * displacement_from_rest * (dy / current_length),
     }
 }
 
 fn calculate_electrostatic_forces_between_nodes(
+    config: &PhysicsConfig,
+    source_node_position: &Position,
+    destination_node_position: &Position,
+) -> Force {
+    let dx = destination_node_position.x - source_node_position.x;
+    let dy = destination_node_position.y - source_node_position.y;
+
+    let distance_between_nodes = (dx * dx + dy * dy).sqrt();
+
+    if distance_between_nodes == 0.0 {
+        return Force { x: 0.0, y: 0.0 };
+    }
+
+    let force_magnitude = config.electrostatic_constant / distance_between_nodes.powi(2);
+
+    Force {
+        x: force_magnitude * (dx / distance_between_nodes),
+        y: force_magnitude * (dy / distance_between_nodes),
+    }
+}
+
+// Pushes from the first node towards the second in proportion to how far their radii overlap.
+fn calculate_collision_forces_between_nodes(
+    config: &PhysicsConfig,
     source_node_position: &Position,
+    source_node_radius: f32,
     destination_node_position: &Position,
+    destination_node_radius: f32,
 ) -> Force {
     let dx = destination_node_position.x - source_node_position.x;
     let dy = destination_node_position.y - source_node_position.y;
 
     let distance_between_nodes = (dx * dx + dy * dy).sqrt();
+    let overlap = source_node_radius + destination_node_radius - distance_between_nodes;
+
+    if overlap <= 0.0 || distance_between_nodes == 0.0 {
+        return Force { x: 0.0, y: 0.0 };
+    }
+
+    let force_magnitude = config.collision_strength * overlap;
 
     Force {
-        x: ELECTROSTATIC_CONSTANT / distance_between_nodes.powi(2) * (dx / distance_between_nodes),
-        y: ELECTROSTATIC_CONSTANT / distance_between_nodes.powi(2) * (dy / distance_between_nodes),
+        x: force_magnitude * (dx / distance_between_nodes),
+        y: force_magnitude * (dy / distance_between_nodes),
     }
 }
 
-fn simulate_time_step(world: &mut World) {
-    for (_id, (position, velocity, force)) in
-        world.query_mut::<(&mut Position, &mut Velocity, &mut Force)>()
-    {
-        position.x += velocity.x * TIME_STEP + 0.5 * force.x * TIME_STEP.powi(2);
-        position.y += velocity.y * TIME_STEP + 0.5 * force.y * TIME_STEP.powi(2);
+fn simulate_time_step(
+    world: &mut World,
+    config: &PhysicsConfig,
+    nodes: &NodeBuffer,
+    forces: Vec<Force>,
+) -> StepStats {
+    let mut stats = StepStats::default();
+
+    for (&entity, accumulated_force) in nodes.entities.iter().zip(forces) {
+        let Ok((position, velocity, force, pinned, frozen)) = world.query_one_mut::<(
+            &mut Position,
+            &mut Velocity,
+            &mut Force,
+            Option<&Pinned>,
+            Option<&Frozen>,
+        )>(entity) else {
+            continue;
+        };
+
+        *force = accumulated_force;
+
+        if pinned.is_some() || frozen.is_some() {
+            *velocity = Velocity { x: 0.0, y: 0.0 };
+            continue;
+        }
+
+        let displacement = integrate(config, position, velocity, force);
+
+        stats.kinetic_energy += 0.5 * (velocity.x.powi(2) + velocity.y.powi(2));
+        stats.max_displacement = stats.max_displacement.max(displacement);
+        stats.moving_nodes += 1;
     }
+
+    stats
 }
 
-pub(crate) fn node_positions_by_id(world: &mut World) -> HashMap<usize, Position> {
-    world
-        .query::<(&Position, &usize)>()
-        .iter()
-        .map(|(_, (pos, &node_id))| (node_id, pos.clone()))
-        .collect()
+// Semi-implicit Euler: the velocity is updated from the force first and the new velocity is then
+// used to move the node, which keeps the springs stable at larger time steps than explicit Euler.
+fn integrate(
+    config: &PhysicsConfig,
+    position: &mut Position,
+    velocity: &mut Velocity,
+    force: &Force,
+) -> f32 {
+    velocity.x = (velocity.x + force.x * config.time_step) * (1.0 - config.damping);
+    velocity.y = (velocity.y + force.y * config.time_step) * (1.0 - config.damping);
+
+    let speed = (velocity.x.powi(2) + velocity.y.powi(2)).sqrt();
+
+    if speed > config.max_speed {
+        velocity.x *= config.max_speed / speed;
+        velocity.y *= config.max_speed / speed;
+    }
+
+    let dx = velocity.x * config.time_step;
+    let dy = velocity.y * config.time_step;
+
+    position.x += dx;
+    position.y += dy;
+
+    (dx * dx + dy * dy).sqrt()
 }
 
-pub(crate) fn edge_by_id(world: &mut World) -> HashMap<usize, Edge> {
+pub(crate) fn edges_by_entity(world: &World) -> Vec<(Entity, Edge)> {
     world
         .query::<&Edge>()
         .iter()
-        .enumerate()
-        .map(|(index, (_, edge))| (index, edge.clone()))
+        .map(|(entity, edge)| (entity, edge.clone()))
         .collect()
 }
 
-fn is_in_range(p0: &Position, p1: &Position) -> bool {
-    ((p0.x - p1.x).powi(2) + (p0.y - p1.y).powi(2)).sqrt() < 100.0
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn should_only_be_settled_below_threshold() {
+        let stats = StepStats {
+            kinetic_energy: 0.01,
+            max_displacement: 0.1,
+            moving_nodes: 2,
+        };
+        let config = |settle_threshold| PhysicsConfig {
+            settle_threshold,
+            ..PhysicsConfig::default()
+        };
+
+        assert!(stats.is_settled(&config(0.2)));
+        assert!(!stats.is_settled(&config(0.1)));
+    }
+
+    #[test]
+    fn should_not_be_settled_while_nodes_still_carry_energy() {
+        let stats = StepStats {
+            kinetic_energy: 2.0,
+            max_displacement: 0.01,
+            moving_nodes: 4,
+        };
+        let config = |settle_energy| PhysicsConfig {
+            settle_energy,
+            ..PhysicsConfig::default()
+        };
+
+        assert!(stats.is_settled(&config(1.0)));
+        assert!(!stats.is_settled(&config(0.1)));
+    }
+
+    #[test]
+    fn should_accelerate_and_damp_velocity() {
+        let mut position = Position { x: 0.0, y: 0.0 };
+        let mut velocity = Velocity { x: 0.0, y: 0.0 };
+
+        let config = PhysicsConfig::default();
+
+        integrate(
+            &config,
+            &mut position,
+            &mut velocity,
+            &Force { x: 100.0, y: 0.0 },
+        );
+
+        assert_eq!(velocity.x, 8.0);
+        assert_eq!(velocity.y, 0.0);
+        assert_eq!(position.x, 0.8);
+
+        integrate(
+            &config,
+            &mut position,
+            &mut velocity,
+            &Force { x: 0.0, y: 0.0 },
+        );
+
+        assert_eq!(velocity.x, 6.4);
+    }
+
+    #[test]
+    fn should_clamp_velocity_to_max_speed() {
+        let mut position = Position { x: 0.0, y: 0.0 };
+        let mut velocity = Velocity { x: 0.0, y: 0.0 };
+
+        let config = PhysicsConfig::default();
+
+        integrate(
+            &config,
+            &mut position,
+            &mut velocity,
+            &Force { x: 0.0, y: 1e6 },
+        );
+
+        assert_eq!(velocity.y, config.max_speed);
+        assert_eq!(position.y, config.max_speed * config.time_step);
+    }
+
     macro_rules! spring_forces_tests {
         ($($name:ident: $value:expr,)*) => {
     $(
         #[test]
         fn $name() {
-            let (first_position, second_position, expected_force) = $value;
-            assert_eq!(expected_force.x, calculate_spring_forces_between_nodes(&first_position, &second_position).x);
-            assert_eq!(expected_force.y, calculate_spring_forces_between_nodes(&first_position, &second_position).y);
+            let (weight, first_position, second_position, expected_force) = $value;
+            assert_eq!(expected_force.x, calculate_spring_forces_between_nodes(&PhysicsConfig::default(), weight, &first_position, &second_position).x);
+            assert_eq!(expected_force.y, calculate_spring_forces_between_nodes(&PhysicsConfig::default(), weight, &first_position, &second_position).y);
         }
     )*
     }}
@@ -204,19 +502,37 @@ mod tests {
         #[test]
         fn $name() {
             let (first_position, second_position, expected_force) = $value;
-            assert_eq!(expected_force.x, calculate_electrostatic_forces_between_nodes(&first_position, &second_position).x);
-            assert_eq!(expected_force.y, calculate_electrostatic_forces_between_nodes(&first_position, &second_position).y);
+            assert_eq!(expected_force.x, calculate_electrostatic_forces_between_nodes(&PhysicsConfig::default(), &first_position, &second_position).x);
+            assert_eq!(expected_force.y, calculate_electrostatic_forces_between_nodes(&PhysicsConfig::default(), &first_position, &second_position).y);
         }
     )*
     }}
 
+    #[test]
+    fn should_only_push_overlapping_nodes_apart() {
+        let config = PhysicsConfig::default();
+        let origin = Position { x: 0.0, y: 0.0 };
+        let neighbour = Position { x: 30.0, y: 0.0 };
+
+        let touching =
+            calculate_collision_forces_between_nodes(&config, &origin, 15.0, &neighbour, 15.0);
+        let overlapping =
+            calculate_collision_forces_between_nodes(&config, &origin, 20.0, &neighbour, 20.0);
+
+        assert_eq!(touching.x, 0.0);
+        assert_eq!(overlapping.x, config.collision_strength * 10.0);
+        assert_eq!(overlapping.y, 0.0);
+    }
+
     spring_forces_tests! {
-            spring_forces_1: (Position { x: 0.0, y: 0.0 }, Position { x: 300.0, y: 0.0 }, Force{ x: -200.0, y: 0.0}),
-            spring_forces_2: (Position { x: 0.0, y: 300.0 }, Position { x: 0.0, y: 0.0 }, Force{ x: 0.0, y: 200.0}),
-            spring_forces_3: (Position { x: 0.0, y: 0.0 }, Position { x: 300.0, y: 300.0 }, Force{ x: -229.28932, y: -229.28932}),
+            spring_forces_1: (1.0, Position { x: 0.0, y: 0.0 }, Position { x: 300.0, y: 0.0 }, Force{ x: -200.0, y: 0.0}),
+            spring_forces_2: (1.0, Position { x: 0.0, y: 300.0 }, Position { x: 0.0, y: 0.0 }, Force{ x: 0.0, y: 200.0}),
+            spring_forces_3: (1.0, Position { x: 0.0, y: 0.0 }, Position { x: 300.0, y: 300.0 }, Force{ x: -229.28932, y: -229.28932}),
+            spring_forces_4: (4.0, Position { x: 0.0, y: 0.0 }, Position { x: 300.0, y: 0.0 }, Force{ x: -1000.0, y: 0.0}),
     }
 
     electrostatic_forces_tests! {
             electrostatic_forces_1: (Position { x: 0.0, y: 0.0 }, Position { x: 10.0, y: 0.0 }, Force{ x: 200.0, y: 0.0}),
+            electrostatic_forces_2: (Position { x: 5.0, y: 5.0 }, Position { x: 5.0, y: 5.0 }, Force{ x: 0.0, y: 0.0}),
     }
 }
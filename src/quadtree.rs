@@ -0,0 +1,234 @@
+use macroquad::math::Vec2;
+
+// Past this depth coincident bodies share a leaf instead of subdividing forever.
+const MAX_DEPTH: usize = 24;
+
+#[derive(Debug)]
+struct Cell {
+    min: Vec2,
+    size: f32,
+    mass: f32,
+    centre_of_mass: Vec2,
+    first_child: Option<usize>,
+    bodies: Vec<(usize, Vec2)>,
+}
+
+impl Cell {
+    fn new(min: Vec2, size: f32) -> Self {
+        Self {
+            min,
+            size,
+            mass: 0.0,
+            centre_of_mass: Vec2::ZERO,
+            first_child: None,
+            bodies: Vec::new(),
+        }
+    }
+
+    fn contains(&self, position: Vec2) -> bool {
+        position.x >= self.min.x
+            && position.y >= self.min.y
+            && position.x <= self.min.x + self.size
+            && position.y <= self.min.y + self.size
+    }
+
+    fn quadrant_of(&self, position: Vec2) -> usize {
+        let half_size = self.size / 2.0;
+        let right = (position.x >= self.min.x + half_size) as usize;
+        let bottom = (position.y >= self.min.y + half_size) as usize;
+
+        bottom * 2 + right
+    }
+}
+
+/// Barnes–Hut quadtree over a set of unit mass bodies, identified by their index in the slice the
+/// tree was built from.
+#[derive(Debug)]
+pub(crate) struct QuadTree {
+    cells: Vec<Cell>,
+}
+
+impl QuadTree {
+    pub(crate) fn new(positions: &[Vec2]) -> Self {
+        let mut min = Vec2::splat(f32::INFINITY);
+        let mut max = Vec2::splat(f32::NEG_INFINITY);
+
+        for &position in positions {
+            min = min.min(position);
+            max = max.max(position);
+        }
+
+        let size = if positions.is_empty() {
+            1.0
+        } else {
+            (max - min).max_element().max(1.0)
+        };
+
+        let mut tree = Self {
+            cells: vec![Cell::new(min, size)],
+        };
+
+        for (index, &position) in positions.iter().enumerate() {
+            tree.insert(index, position);
+        }
+
+        for cell in tree.cells.iter_mut() {
+            if cell.mass > 0.0 {
+                cell.centre_of_mass /= cell.mass;
+            }
+        }
+
+        tree
+    }
+
+    fn insert(&mut self, index: usize, position: Vec2) {
+        let mut cell_index = 0;
+        let mut depth = 0;
+
+        loop {
+            let cell = &mut self.cells[cell_index];
+            cell.mass += 1.0;
+            cell.centre_of_mass += position;
+
+            match cell.first_child {
+                Some(first_child) => {
+                    cell_index = first_child + cell.quadrant_of(position);
+                }
+                None if cell.bodies.is_empty() || depth == MAX_DEPTH => {
+                    cell.bodies.push((index, position));
+                    return;
+                }
+                None => {
+                    let first_child = self.subdivide(cell_index);
+                    cell_index = first_child + self.cells[cell_index].quadrant_of(position);
+                }
+            }
+
+            depth += 1;
+        }
+    }
+
+    fn subdivide(&mut self, cell_index: usize) -> usize {
+        let first_child = self.cells.len();
+        let min = self.cells[cell_index].min;
+        let half_size = self.cells[cell_index].size / 2.0;
+
+        for quadrant in 0..4 {
+            let offset = Vec2::new((quadrant % 2) as f32, (quadrant / 2) as f32) * half_size;
+            self.cells.push(Cell::new(min + offset, half_size));
+        }
+
+        let bodies = std::mem::take(&mut self.cells[cell_index].bodies);
+        self.cells[cell_index].first_child = Some(first_child);
+
+        for (index, position) in bodies {
+            let quadrant = self.cells[cell_index].quadrant_of(position);
+            let child = &mut self.cells[first_child + quadrant];
+            child.mass += 1.0;
+            child.centre_of_mass += position;
+            child.bodies.push((index, position));
+        }
+
+        first_child
+    }
+
+    /// Calls `visit` with the position and mass of every body or group of bodies acting on the
+    /// body at `index`. Cells whose size divided by their distance is below `theta` are treated as
+    /// a single body at their centre of mass, so a `theta` of zero visits every other body exactly.
+    pub(crate) fn for_each_influence(
+        &self,
+        index: usize,
+        position: Vec2,
+        theta: f32,
+        mut visit: impl FnMut(Vec2, f32),
+    ) {
+        let mut stack = vec![0];
+
+        while let Some(cell_index) = stack.pop() {
+            let cell = &self.cells[cell_index];
+
+            if cell.mass == 0.0 {
+                continue;
+            }
+
+            match cell.first_child {
+                None => {
+                    for &(body_index, body_position) in &cell.bodies {
+                        if body_index != index {
+                            visit(body_position, 1.0);
+                        }
+                    }
+                }
+                Some(first_child) => {
+                    let distance = position.distance(cell.centre_of_mass);
+
+                    if !cell.contains(position) && cell.size < theta * distance {
+                        visit(cell.centre_of_mass, cell.mass);
+                    } else {
+                        stack.extend(first_child..first_child + 4);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_visit_every_other_body_when_theta_is_zero() {
+        let positions = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(100.0, 100.0),
+        ];
+        let tree = QuadTree::new(&positions);
+
+        let mut visited = Vec::new();
+        tree.for_each_influence(0, positions[0], 0.0, |position, mass| {
+            visited.push((position, mass))
+        });
+
+        assert_eq!(visited.len(), 3);
+        assert!(visited.iter().all(|&(_, mass)| mass == 1.0));
+        assert!(!visited
+            .iter()
+            .any(|&(position, _)| position == positions[0]));
+    }
+
+    #[test]
+    fn should_group_distant_bodies() {
+        let positions = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1000.0, 1000.0),
+            Vec2::new(1001.0, 1000.0),
+            Vec2::new(1000.0, 1001.0),
+            Vec2::new(1001.0, 1001.0),
+        ];
+        let tree = QuadTree::new(&positions);
+
+        let mut visited = Vec::new();
+        tree.for_each_influence(0, positions[0], 1.0, |position, mass| {
+            visited.push((position, mass))
+        });
+
+        let total_mass: f32 = visited.iter().map(|&(_, mass)| mass).sum();
+
+        assert!(visited.len() < 4);
+        assert_eq!(total_mass, 4.0);
+    }
+
+    #[test]
+    fn should_keep_coincident_bodies() {
+        let positions = vec![Vec2::new(5.0, 5.0); 3];
+        let tree = QuadTree::new(&positions);
+
+        let mut visited = 0;
+        tree.for_each_influence(0, positions[0], 0.5, |_, _| visited += 1);
+
+        assert_eq!(visited, 2);
+    }
+}
@@ -1,236 +1,2255 @@
-use crate::graph::{Position, Size};
-use crate::physics::{edge_by_id, node_positions_by_id};
-use hecs::World;
-use macroquad::color::{Color, BLACK, DARKGRAY, RED, WHITE};
-use macroquad::input::{is_mouse_button_down, mouse_delta_position};
-use macroquad::math::Vec2;
-use macroquad::prelude::{clear_background, draw_circle, draw_line};
-use macroquad::text::{draw_text, get_text_center};
+use crate::graph::{Cluster, Edge, Metadata, NodeId, Pinned, Position, Size, Velocity};
+use crate::physics::edges_by_entity;
+use crate::style::{ArrowHead, EdgeStyle, LinePattern, NodeShape, NodeStyle};
+use hecs::{Entity, World};
+use macroquad::color::{Color, BLACK, DARKGRAY, LIGHTGRAY, SKYBLUE, WHITE, YELLOW};
+use macroquad::input::{
+    is_key_down, is_key_pressed, is_mouse_button_down, is_mouse_button_pressed, mouse_position,
+    mouse_wheel, touches, KeyCode, MouseButton,
+};
+use macroquad::math::{Rect, Vec2};
+use macroquad::prelude::{
+    draw_arc, draw_circle, draw_circle_lines, draw_line, draw_poly, draw_poly_lines,
+    draw_rectangle, draw_rectangle_lines, draw_triangle,
+};
+use macroquad::text::{draw_text, draw_text_ex, get_text_center, measure_text, TextParams};
+use macroquad::time::{get_frame_time, get_time};
+use macroquad::window::{get_internal_gl, screen_dpi_scale, screen_height, screen_width};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::f32::consts::{PI, TAU};
 
-const NODE_SIZE: f32 = 15.0;
+const MAX_EDGE_THICKNESS: f32 = 12.0;
+const ARROWHEAD_SIZE: f32 = 15.0;
+const LANE_SPACING: f32 = 30.0;
+const SELF_LOOP_RADIUS: f32 = 12.0;
+const CURVE_SEGMENTS: usize = 16;
+// Dash and dot spacing, in multiples of the line thickness.
+const DASH_LENGTH: f32 = 4.0;
+const DASH_GAP: f32 = 2.0;
+const DOT_SPACING: f32 = 2.5;
+const ROUNDED_CORNER_RATIO: f32 = 0.4;
+const EDGE_LABEL_FONT_SIZE: u16 = 16;
+const EDGE_LABEL_GAP: f32 = 4.0;
+// Where along an edge its label may sit, tried in order until one clears every node.
+const EDGE_LABEL_ANCHORS: [f32; 7] = [0.5, 0.4, 0.6, 0.3, 0.7, 0.2, 0.8];
+// Text is not drawn once zooming out would shrink it below this size.
+const MIN_FONT_SIZE: f32 = 4.0;
+const MIN_SCALE: f32 = 0.05;
+const MAX_SCALE: f32 = 10.0;
+// How much one notch of the mouse wheel zooms by.
+const ZOOM_STEP: f32 = 1.1;
+// Keyboard panning speed in screen pixels per second.
+const KEY_PAN_SPEED: f32 = 600.0;
+// Fraction of the fling speed kept after a second of coasting, and the speed in screen pixels per
+// second below which it stops.
+const FLING_FRICTION: f32 = 0.05;
+const MIN_FLING_SPEED: f32 = 5.0;
+// Seconds within which a second click on the same node counts as a double-click.
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+const PIN_MARKER_RADIUS: f32 = 3.0;
+// Screen pixels left around the graph when fitting it to the view.
+const FIT_MARGIN: f32 = 40.0;
+// How quickly the view catches up with the centroid when following it, per second.
+const FOLLOW_RATE: f32 = 5.0;
+// How far from an edge, in screen pixels, the cursor still counts as hovering over it.
+const HOVER_TOLERANCE: f32 = 4.0;
+// Opacity of everything not connected to what is being hovered over.
+const DIM_ALPHA: f32 = 0.25;
+const HIGHLIGHT_WIDTH: f32 = 3.0;
+const TOOLTIP_FONT_SIZE: u16 = 16;
+const TOOLTIP_PADDING: f32 = 6.0;
+// Distance from the cursor to the corner of the tooltip, in screen pixels.
+const TOOLTIP_OFFSET: f32 = 16.0;
+// Screen pixels the cursor may move between press and release for it to still count as a click.
+const CLICK_DISTANCE: f32 = 4.0;
+// World units left between the outline of a cluster and the nodes inside it.
+const CLUSTER_PADDING: f32 = 20.0;
+const CLUSTER_FONT_SIZE: u16 = 16;
+// Screen pixels between the top left corner of a cluster's outline and its caption.
+const CLUSTER_CAPTION_INSET: f32 = 6.0;
+// Selection events kept for a caller that is not draining them, after which the oldest are dropped.
+const MAX_SELECTION_EVENTS: usize = 256;
 
+#[derive(Debug, Clone)]
 pub struct ScrollableView {
+    /// The world position shown in the top left corner of the view.
     pub offset: Vec2,
+    /// Screen pixels per world unit.
+    pub scale: f32,
+    // The zoom limits, kept positive and in order by `set_zoom_limits` so clamping is safe.
+    min_scale: f32,
+    max_scale: f32,
+    /// Keeps the view moving after a drag is released, slowing down until it stops.
+    pub fling: bool,
+    /// Keeps the centre of the graph in the middle of the view while the layout settles.
+    pub follow_centroid: bool,
+    // The part of the screen the view is drawn into, or the whole screen if not set.
+    viewport: Option<Rect>,
+    drag_position: Option<Vec2>,
+    // Screen pixels per second the content is moving at, from the drag or fling.
+    velocity: Vec2,
+    pinch_distance: Option<f32>,
+    dragged_node: Option<DraggedNode>,
+    last_click: Option<(Entity, f64)>,
+    hovered: Option<Hovered>,
+    // Where the mouse was pressed over empty space, in screen pixels, to tell clicks from pans.
+    empty_press_position: Option<Vec2>,
+    // The world positions of the corners of the box being dragged out to select nodes.
+    selection_box: Option<(Vec2, Vec2)>,
+    selection_events: VecDeque<SelectionEvent>,
+}
+
+/// A node being added to or removed from the selection by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionEvent {
+    Selected { id: NodeId, label: String },
+    Deselected { id: NodeId, label: String },
+}
+
+// Marks a node selected by the user.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Selected;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Hovered {
+    Node(Entity),
+    Edge(Entity),
+}
+
+// The nodes and edges left undimmed while something is hovered over.
+#[derive(Debug, Default)]
+struct Highlight {
+    node_ids: HashSet<usize>,
+    edges: HashSet<Entity>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DraggedNode {
+    entity: Entity,
+    // Where the node is relative to the cursor, so it does not jump to centre on the cursor.
+    grab_offset: Vec2,
+    // Whether the node stays pinned once it is let go of.
+    is_pinned_after: bool,
 }
 
 impl ScrollableView {
-    pub fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             offset: Vec2::new(0.0, 0.0),
+            scale: 1.0,
+            min_scale: MIN_SCALE,
+            max_scale: MAX_SCALE,
+            fling: false,
+            follow_centroid: false,
+            viewport: None,
+            drag_position: None,
+            velocity: Vec2::ZERO,
+            pinch_distance: None,
+            dragged_node: None,
+            last_click: None,
+            hovered: None,
+            empty_press_position: None,
+            selection_box: None,
+            selection_events: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn world_pos_to_screen_pos(&self, world_pos: &Position) -> Position {
+        let origin = self.viewport_origin();
+
+        Position {
+            x: (world_pos.x - self.offset.x) * self.scale + origin.x,
+            y: (world_pos.y - self.offset.y) * self.scale + origin.y,
         }
     }
 
-    pub fn world_pos_to_screen_pos(&self, world_pos: &Position) -> Position {
+    pub(crate) fn screen_pos_to_world_pos(&self, screen_pos: &Position) -> Position {
+        let origin = self.viewport_origin();
+
         Position {
-            x: world_pos.x - self.offset.x,
-            y: world_pos.y - self.offset.y,
+            x: (screen_pos.x - origin.x) / self.scale + self.offset.x,
+            y: (screen_pos.y - origin.y) / self.scale + self.offset.y,
+        }
+    }
+
+    /// Where a world position is on screen, in screen pixels.
+    pub fn to_screen(&self, world_pos: Vec2) -> Vec2 {
+        (world_pos - self.offset) * self.scale + self.viewport_origin()
+    }
+
+    /// The world position at a point on screen.
+    pub fn to_world(&self, screen_pos: Vec2) -> Vec2 {
+        (screen_pos - self.viewport_origin()) / self.scale + self.offset
+    }
+
+    fn viewport_origin(&self) -> Vec2 {
+        self.viewport
+            .map_or(Vec2::ZERO, |viewport| viewport.point())
+    }
+
+    /// The part of the screen the view is drawn into.
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+            .unwrap_or_else(|| Rect::new(0.0, 0.0, screen_width(), screen_height()))
+    }
+
+    pub(crate) fn set_viewport(&mut self, viewport: Rect) {
+        self.viewport = Some(viewport);
+    }
+
+    fn push_selection_event(&mut self, event: SelectionEvent) {
+        if self.selection_events.len() == MAX_SELECTION_EVENTS {
+            self.selection_events.pop_front();
+        }
+
+        self.selection_events.push_back(event);
+    }
+
+    /// The smallest and largest scale zooming is allowed to reach.
+    pub fn zoom_limits(&self) -> (f32, f32) {
+        (self.min_scale, self.max_scale)
+    }
+
+    /// Limits zooming to scales between `min_scale` and `max_scale`, in either order, and brings
+    /// the current scale within them. Limits that are not positive and finite are ignored and
+    /// `false` returned.
+    pub fn set_zoom_limits(&mut self, min_scale: f32, max_scale: f32) -> bool {
+        let is_valid = |scale: f32| scale.is_finite() && scale > 0.0;
+
+        if !is_valid(min_scale) || !is_valid(max_scale) {
+            return false;
+        }
+
+        self.min_scale = min_scale.min(max_scale);
+        self.max_scale = min_scale.max(max_scale);
+        self.scale = self.scale.clamp(self.min_scale, self.max_scale);
+        true
+    }
+
+    /// Multiplies the scale by `factor`, within the zoom limits, keeping the world position under
+    /// `screen_pos` where it is on screen.
+    pub fn zoom_at(&mut self, screen_pos: Vec2, factor: f32) {
+        let world_pos = self.screen_pos_to_world_pos(&Position {
+            x: screen_pos.x,
+            y: screen_pos.y,
+        });
+
+        self.scale = (self.scale * factor).clamp(self.min_scale, self.max_scale);
+        self.offset = Vec2::new(world_pos.x, world_pos.y)
+            - (screen_pos - self.viewport_origin()) / self.scale;
+    }
+
+    // Zooms and scrolls so every node fits in a view of `screen_size` pixels.
+    pub(crate) fn fit_to_view(&mut self, world: &World, screen_size: Vec2) {
+        if let Some((min, max)) = graph_bounds(world) {
+            self.fit_to_bounds(min, max, screen_size);
+        }
+    }
+
+    /// Zooms and scrolls so the box between the world positions `min` and `max` fills a screen of
+    /// `screen_size` pixels, leaving a margin around it.
+    pub fn fit_to_bounds(&mut self, min: Vec2, max: Vec2, screen_size: Vec2) {
+        let size = (max - min).max(Vec2::ONE);
+        let available_size = (screen_size - Vec2::splat(2.0 * FIT_MARGIN)).max(Vec2::ONE);
+
+        self.scale = (available_size / size)
+            .min_element()
+            .clamp(self.min_scale, self.max_scale);
+        self.velocity = Vec2::ZERO;
+        self.centre_on((min + max) / 2.0, screen_size);
+    }
+
+    /// Scrolls so `world_pos` is in the middle of a view of `screen_size` pixels.
+    pub fn centre_on(&mut self, world_pos: Vec2, screen_size: Vec2) {
+        self.offset = world_pos - screen_size / 2.0 / self.scale;
+    }
+
+    fn follow(&mut self, world_pos: Vec2, screen_size: Vec2, delta_time: f32) {
+        let target_offset = world_pos - screen_size / 2.0 / self.scale;
+
+        self.offset = self
+            .offset
+            .lerp(target_offset, 1.0 - (-FOLLOW_RATE * delta_time).exp());
+    }
+
+    /// Moves the content of the view by `screen_delta` pixels.
+    pub fn pan(&mut self, screen_delta: Vec2) {
+        self.offset -= screen_delta / self.scale;
+    }
+
+    // Keeps whatever was under the cursor when the drag started under the cursor.
+    fn drag_to(&mut self, mouse_pos: Vec2, delta_time: f32) {
+        match self.drag_position {
+            // Grabbing the view stops it from coasting.
+            None => self.velocity = Vec2::ZERO,
+            Some(previous_pos) => {
+                let screen_delta = mouse_pos - previous_pos;
+
+                self.pan(screen_delta);
+
+                if delta_time > 0.0 {
+                    // Smoothed so one uneven frame at the end of a drag does not decide the fling.
+                    self.velocity = self.velocity.lerp(screen_delta / delta_time, 0.5);
+                }
+            }
+        }
+
+        self.drag_position = Some(mouse_pos);
+    }
+
+    fn release(&mut self) {
+        if self.drag_position.take().is_some() && !self.fling {
+            self.velocity = Vec2::ZERO;
+        }
+    }
+
+    fn coast(&mut self, delta_time: f32) {
+        if self.velocity.length() < MIN_FLING_SPEED {
+            self.velocity = Vec2::ZERO;
+            return;
         }
+
+        self.pan(self.velocity * delta_time);
+        self.velocity *= FLING_FRICTION.powf(delta_time);
     }
 
+    /// Pans and zooms the view from the mouse, keyboard and touch input of this frame. Input only
+    /// reaches the view while the cursor is over it, though a drag that started over the view
+    /// carries on outside it.
     pub fn update(&mut self) {
-        if is_mouse_button_down(macroquad::input::MouseButton::Left) {
-            let mouse_delta = mouse_delta_position();
+        let delta_time = get_frame_time();
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse_pos = Vec2::new(mouse_x, mouse_y);
+        let is_mouse_over = self.viewport().contains(mouse_pos);
+        let is_drag_starting = is_mouse_over && is_mouse_button_pressed(MouseButton::Left);
+
+        if is_mouse_button_down(MouseButton::Left)
+            && (self.drag_position.is_some() || is_drag_starting)
+            && self.dragged_node.is_none()
+            && self.selection_box.is_none()
+        {
+            self.drag_to(mouse_pos, delta_time);
+        } else {
+            self.release();
+            self.coast(delta_time);
+        }
+
+        if !is_mouse_over {
+            self.pinch_distance = None;
+            return;
+        }
+
+        let key_direction = key_pan_direction();
+
+        if key_direction != Vec2::ZERO {
+            // The arrows move the view, so the content moves the other way.
+            self.pan(-key_direction * KEY_PAN_SPEED * delta_time);
+        }
+
+        let (_, wheel_y) = mouse_wheel();
+
+        if wheel_y != 0.0 {
+            // Some platforms report a notch as 1 and others as 120, while trackpads report
+            // fractions of a notch.
+            self.zoom_at(mouse_pos, ZOOM_STEP.powf(wheel_y.clamp(-1.0, 1.0)));
+        }
+
+        self.update_pinch();
+    }
 
-            self.offset.x += mouse_delta.x * 1000.0;
-            self.offset.y += mouse_delta.y * 1000.0;
+    fn update_pinch(&mut self) {
+        let touches = touches();
+
+        let [first, second] = &touches[..] else {
+            self.pinch_distance = None;
+            return;
+        };
+
+        let distance = first.position.distance(second.position);
+
+        if let Some(previous_distance) = self.pinch_distance.filter(|&distance| distance > 0.0) {
+            self.zoom_at(
+                (first.position + second.position) / 2.0,
+                distance / previous_distance,
+            );
         }
+
+        self.pinch_distance = Some(distance);
     }
 }
 
-pub(crate) fn render(world: &mut World) {
-    clear_background(DARKGRAY);
+fn key_pan_direction() -> Vec2 {
+    let mut direction = Vec2::ZERO;
+
+    if is_key_down(KeyCode::Left) || is_key_down(KeyCode::A) {
+        direction.x -= 1.0;
+    }
+    if is_key_down(KeyCode::Right) || is_key_down(KeyCode::D) {
+        direction.x += 1.0;
+    }
+    if is_key_down(KeyCode::Up) || is_key_down(KeyCode::W) {
+        direction.y -= 1.0;
+    }
+    if is_key_down(KeyCode::Down) || is_key_down(KeyCode::S) {
+        direction.y += 1.0;
+    }
 
-    render_edges(world);
-    render_nodes(&world);
+    direction.normalize_or_zero()
 }
 
-pub(crate) fn view_port_update(world: &mut World) {
-    let mut scrollable_view_query = world.query::<&mut ScrollableView>();
+// Draws the graph into the view's part of the screen, leaving the rest of the screen untouched.
+// `data_lines` describe the data of the hovered node, if any, and are added to its tooltip.
+pub(crate) fn render(world: &mut World, data_lines: &[String]) {
+    let view = current_view(world);
+    let viewport = view.viewport();
+    let dpi_scale = screen_dpi_scale();
 
-    match scrollable_view_query.iter().last() {
-        None => {}
-        Some((_e, scrollable_view)) => {
-            scrollable_view.update();
-        }
+    // SAFETY: no other reference to the macroquad context is held while the clip is set.
+    unsafe { get_internal_gl() }.quad_gl.scissor(Some((
+        (viewport.x * dpi_scale) as i32,
+        (viewport.y * dpi_scale) as i32,
+        (viewport.w * dpi_scale) as i32,
+        (viewport.h * dpi_scale) as i32,
+    )));
+
+    draw_rectangle(viewport.x, viewport.y, viewport.w, viewport.h, DARKGRAY);
+
+    let highlight = view
+        .hovered
+        .map(|hovered| calculate_highlight(world, hovered));
+
+    render_clusters(world, &view);
+    render_edges(world, highlight.as_ref());
+    render_nodes(world, highlight.as_ref());
+    render_selection_box(&view);
+    render_tooltip(world, data_lines);
+
+    // SAFETY: as above.
+    unsafe { get_internal_gl() }.quad_gl.scissor(None);
+}
+
+fn render_clusters(world: &World, view: &ScrollableView) {
+    for (name, (min, max)) in cluster_bounds(world) {
+        let (min, max) = (view.to_screen(min), view.to_screen(max));
+        let size = max - min;
+
+        draw_rectangle(min.x, min.y, size.x, size.y, Color { a: 0.08, ..WHITE });
+        draw_rectangle_lines(min.x, min.y, size.x, size.y, 1.5, LIGHTGRAY);
+        draw_text(
+            &name,
+            min.x + CLUSTER_CAPTION_INSET,
+            min.y + CLUSTER_CAPTION_INSET + CLUSTER_FONT_SIZE as f32,
+            CLUSTER_FONT_SIZE as f32,
+            LIGHTGRAY,
+        );
     }
 }
 
-fn render_edges(world: &mut World) {
-    let node_data = node_positions_by_id(world);
-    let edge_data = edge_by_id(world);
+// The box around the nodes of each cluster, padded and in world units, by cluster name.
+fn cluster_bounds(world: &World) -> BTreeMap<String, (Vec2, Vec2)> {
+    let mut bounds: BTreeMap<String, (Vec2, Vec2)> = BTreeMap::new();
 
-    for (_, edge) in edge_data {
-        let edge_source_node_id = edge.source_node_id;
-        let edge_destination_node_id = edge.destination_node_id;
+    for (_, (position, size, Cluster(name))) in world.query::<(&Position, &Size, &Cluster)>().iter()
+    {
+        let centre = Vec2::new(position.x, position.y);
+        let extent = Vec2::splat(size.radius + CLUSTER_PADDING);
+        let (node_min, node_max) = (centre - extent, centre + extent);
 
-        if node_data.contains_key(&edge_source_node_id)
-            && node_data.contains_key(&edge_destination_node_id)
-        {
-            match (
-                node_data.get(&edge_source_node_id),
-                node_data.get(&edge_destination_node_id),
-            ) {
-                (Some(source_node_position), Some(destination_node_position)) => {
-                    let source_node_position =
-                        world_to_screen_position(world, source_node_position);
-                    let destination_node_position =
-                        world_to_screen_position(world, destination_node_position);
-
-                    draw_arrow_line(
-                        Vec2 {
-                            x: source_node_position.x,
-                            y: source_node_position.y,
-                        },
-                        Vec2 {
-                            x: destination_node_position.x,
-                            y: destination_node_position.y,
-                        },
-                        RED,
-                        3.0,
-                        edge.is_directed,
-                    )
+        bounds
+            .entry(name.clone())
+            .and_modify(|(min, max)| {
+                *min = min.min(node_min);
+                *max = max.max(node_max);
+            })
+            .or_insert((node_min, node_max));
+    }
+
+    bounds
+}
+
+fn render_selection_box(view: &ScrollableView) {
+    let Some((corner, opposite_corner)) = view.selection_box else {
+        return;
+    };
+
+    let (corner, opposite_corner) = (view.to_screen(corner), view.to_screen(opposite_corner));
+    let (min, size) = (
+        corner.min(opposite_corner),
+        (opposite_corner - corner).abs(),
+    );
+
+    draw_rectangle(min.x, min.y, size.x, size.y, Color { a: 0.2, ..SKYBLUE });
+    draw_rectangle_lines(min.x, min.y, size.x, size.y, 1.0, SKYBLUE);
+}
+
+fn calculate_highlight(world: &World, hovered: Hovered) -> Highlight {
+    let mut highlight = Highlight::default();
+
+    match hovered {
+        Hovered::Node(entity) => {
+            let Ok(node_id) = world.get::<&usize>(entity).map(|node_id| *node_id) else {
+                return highlight;
+            };
+
+            highlight.node_ids.insert(node_id);
+
+            for (edge_entity, edge) in world.query::<&Edge>().iter() {
+                if edge.source_node_id == node_id || edge.destination_node_id == node_id {
+                    highlight.edges.insert(edge_entity);
+                    highlight.node_ids.insert(edge.source_node_id);
+                    highlight.node_ids.insert(edge.destination_node_id);
                 }
-                _ => {}
             }
-        };
+        }
+        Hovered::Edge(entity) => {
+            if let Ok(edge) = world.get::<&Edge>(entity) {
+                highlight.edges.insert(entity);
+                highlight.node_ids.insert(edge.source_node_id);
+                highlight.node_ids.insert(edge.destination_node_id);
+            }
+        }
+    }
+
+    highlight
+}
+
+fn dim(color: Color) -> Color {
+    Color {
+        a: color.a * DIM_ALPHA,
+        ..color
+    }
+}
+
+// The full label of a hovered node followed by its metadata and `data_lines`, or the nodes a
+// hovered edge connects followed by its label and weight.
+fn tooltip_lines(world: &World, hovered: Hovered, data_lines: &[String]) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    match hovered {
+        Hovered::Node(entity) => {
+            let Ok(mut query) = world.query_one::<(&String, &Metadata)>(entity) else {
+                return lines;
+            };
+            let Some((label, Metadata(metadata))) = query.get() else {
+                return lines;
+            };
+
+            lines.push(label.clone());
+            lines.extend(
+                metadata
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value)),
+            );
+            lines.extend_from_slice(data_lines);
+        }
+        Hovered::Edge(entity) => {
+            let Ok(edge) = world.get::<&Edge>(entity).map(|edge| (*edge).clone()) else {
+                return lines;
+            };
+
+            let label_of = |node_id: usize| {
+                world
+                    .query::<(&usize, &String)>()
+                    .iter()
+                    .find(|(_, (&id, _))| id == node_id)
+                    .map(|(_, (_, label))| label.clone())
+                    .unwrap_or_default()
+            };
+            let arrow = if edge.is_directed { "→" } else { "—" };
+
+            lines.push(format!(
+                "{} {} {}",
+                label_of(edge.source_node_id),
+                arrow,
+                label_of(edge.destination_node_id)
+            ));
+            lines.extend(edge.label.clone());
+
+            if edge.weight != 1.0 {
+                lines.push(format!("weight: {}", edge.weight));
+            }
+        }
     }
+
+    lines
 }
 
-fn render_nodes(world: &&mut World) {
-    for (_id, (position, size, label)) in &mut world.query::<(&Position, &Size, &String)>() {
-        let label = &label;
-        let center_of_text = get_text_center(label, None, 20, 1.0, 0.0);
+fn render_tooltip(world: &World, data_lines: &[String]) {
+    let view = current_view(world);
+    let Some(hovered) = view.hovered else {
+        return;
+    };
 
-        let mut scrollable_view_query = world.query::<&ScrollableView>();
-        let (_, scrollable_view) = scrollable_view_query
+    let lines = tooltip_lines(world, hovered, data_lines);
+    let line_dimensions: Vec<_> = lines
+        .iter()
+        .map(|line| measure_text(line, None, TOOLTIP_FONT_SIZE, 1.0))
+        .collect();
+    let line_height = TOOLTIP_FONT_SIZE as f32;
+    let size = Vec2::new(
+        line_dimensions
             .iter()
-            .next()
-            .expect("No scrollable view found");
-        let position = scrollable_view.world_pos_to_screen_pos(position);
+            .map(|dimensions| dimensions.width)
+            .fold(0.0, f32::max),
+        line_height * lines.len() as f32,
+    ) + Vec2::splat(2.0 * TOOLTIP_PADDING);
+
+    // Kept in the view by flipping to the other side of the cursor near the right and bottom edges.
+    let (mouse_x, mouse_y) = mouse_position();
+    let mouse_pos = Vec2::new(mouse_x, mouse_y);
+    let mut corner = mouse_pos + Vec2::splat(TOOLTIP_OFFSET);
+    let viewport = view.viewport();
+
+    if corner.x + size.x > viewport.right() {
+        corner.x = mouse_pos.x - TOOLTIP_OFFSET - size.x;
+    }
+    if corner.y + size.y > viewport.bottom() {
+        corner.y = mouse_pos.y - TOOLTIP_OFFSET - size.y;
+    }
+
+    draw_rectangle(
+        corner.x,
+        corner.y,
+        size.x,
+        size.y,
+        Color { a: 0.85, ..BLACK },
+    );
 
-        draw_circle(position.x, position.y, size.radius, BLACK);
+    for (index, (line, dimensions)) in lines.iter().zip(&line_dimensions).enumerate() {
         draw_text(
-            label,
-            position.x - center_of_text.x,
-            position.y - center_of_text.y / 2.0,
-            20.0,
+            line,
+            corner.x + TOOLTIP_PADDING,
+            corner.y + TOOLTIP_PADDING + line_height * index as f32 + dimensions.offset_y,
+            TOOLTIP_FONT_SIZE as f32,
             WHITE,
         );
     }
 }
 
-fn world_to_screen_position(world: &mut World, source_node_position: &Position) -> Position {
-    let mut scrollable_view_query = world.query::<&ScrollableView>();
-    let (_, scrollable_view) = scrollable_view_query
+// Handles input for the view and for dragging nodes around. Returns whether any node was moved or
+// pinned, in which case the simulation needs to run again.
+pub(crate) fn view_port_update(world: &mut World, is_settled: bool) -> bool {
+    let Some(view_entity) = world
+        .query::<&ScrollableView>()
         .iter()
-        .next()
-        .expect("No scrollable view found");
-    let source_node_position = scrollable_view.world_pos_to_screen_pos(source_node_position);
-    source_node_position
+        .last()
+        .map(|(entity, _)| entity)
+    else {
+        return false;
+    };
+
+    let mut view = current_view(world);
+    let (mouse_x, mouse_y) = mouse_position();
+    let mouse_pos = view.screen_pos_to_world_pos(&Position {
+        x: mouse_x,
+        y: mouse_y,
+    });
+    let mouse_pos = Vec2::new(mouse_pos.x, mouse_pos.y);
+
+    let is_mouse_over = view.viewport().contains(Vec2::new(mouse_x, mouse_y));
+    let is_shift_down = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+
+    if is_mouse_over && is_mouse_button_pressed(MouseButton::Left) {
+        match node_at(world, mouse_pos) {
+            Some(entity) => {
+                select_node(world, &mut view, entity, is_shift_down);
+                grab_node(world, &mut view, entity, mouse_pos, get_time());
+            }
+            None if is_shift_down => view.selection_box = Some((mouse_pos, mouse_pos)),
+            None => view.empty_press_position = Some(Vec2::new(mouse_x, mouse_y)),
+        }
+    }
+
+    if let Some((box_start, _)) = view.selection_box {
+        if is_mouse_button_down(MouseButton::Left) {
+            view.selection_box = Some((box_start, mouse_pos));
+        } else {
+            for entity in nodes_in_box(world, box_start, mouse_pos) {
+                set_selected(world, &mut view, entity, true);
+            }
+
+            view.selection_box = None;
+        }
+    }
+
+    if let Some(press_position) = view.empty_press_position {
+        if !is_mouse_button_down(MouseButton::Left) {
+            if press_position.distance(Vec2::new(mouse_x, mouse_y)) < CLICK_DISTANCE {
+                clear_selection(world, &mut view);
+            }
+
+            view.empty_press_position = None;
+        }
+    }
+
+    let has_moved_nodes = match view.dragged_node {
+        None => false,
+        Some(dragged_node) if is_mouse_button_down(MouseButton::Left) => {
+            move_dragged_node(world, dragged_node, mouse_pos);
+            true
+        }
+        Some(dragged_node) => {
+            release_node(world, dragged_node);
+            view.dragged_node = None;
+            true
+        }
+    };
+
+    view.hovered = match view.dragged_node {
+        Some(dragged_node) => Some(Hovered::Node(dragged_node.entity)),
+        None if !is_mouse_over => None,
+        None => node_at(world, mouse_pos)
+            .map(Hovered::Node)
+            .or_else(|| edge_at(world, mouse_pos, HOVER_TOLERANCE / view.scale).map(Hovered::Edge)),
+    };
+
+    view.update();
+
+    let screen_size = view.viewport().size();
+
+    if is_mouse_over && is_key_pressed(KeyCode::F) {
+        view.fit_to_view(world, screen_size);
+    }
+
+    let is_user_moving_view = view.drag_position.is_some() || view.dragged_node.is_some();
+
+    if view.follow_centroid && !is_settled && !is_user_moving_view {
+        if let Some(centroid) = graph_centroid(world) {
+            view.follow(centroid, screen_size, get_frame_time());
+        }
+    }
+
+    *world.get::<&mut ScrollableView>(view_entity).unwrap() = view;
+
+    has_moved_nodes
 }
 
-fn render_arrow(
-    color: Color,
-    thickness: f32,
-    arrow_start: Vec2,
-    arrow_end: Vec2,
-    arrowhead_left: Vec2,
-    arrowhead_right: Vec2,
-    is_directed: bool,
-) {
-    // Draw main line
-    draw_line(
-        arrow_start.x,
-        arrow_start.y,
-        arrow_end.x,
-        arrow_end.y,
-        thickness,
-        color,
-    );
+// Stops the view from referring to a node or edge that is about to be despawned, deselecting it
+// first if it is a selected node.
+pub(crate) fn forget_entity(world: &mut World, entity: Entity) {
+    let mut view = current_view(world);
 
-    if (is_directed) {
-        // draw left side of arrow head
-        draw_line(
-            arrow_end.x,
-            arrow_end.y,
-            arrowhead_left.x,
-            arrowhead_left.y,
-            thickness,
-            color,
-        );
-        // draw right side of arrow head
-        draw_line(
-            arrow_end.x,
-            arrow_end.y,
-            arrowhead_right.x,
-            arrowhead_right.y,
-            thickness,
-            color,
-        );
+    set_selected(world, &mut view, entity, false);
+
+    if let Some(Hovered::Node(hovered) | Hovered::Edge(hovered)) = view.hovered {
+        if hovered == entity {
+            view.hovered = None;
+        }
+    }
+    if view
+        .dragged_node
+        .is_some_and(|dragged_node| dragged_node.entity == entity)
+    {
+        view.dragged_node = None;
+    }
+    if view
+        .last_click
+        .is_some_and(|(clicked_entity, _)| clicked_entity == entity)
+    {
+        view.last_click = None;
+    }
+
+    for (_, stored_view) in world.query_mut::<&mut ScrollableView>() {
+        *stored_view = view.clone();
     }
 }
 
-fn calculate_arrow_positions(start_pos: Vec2, end_pos: Vec2) -> (Vec2, Vec2, Vec2, Vec2) {
-    // Calculate direction vector
-    let direction = (end_pos - start_pos).normalize();
+// The id of the node the cursor is over, if it is over one.
+pub(crate) fn hovered_node_id(world: &World) -> Option<usize> {
+    let Some(Hovered::Node(entity)) = current_view(world).hovered else {
+        return None;
+    };
 
-    // Calculate arrow start and end points (edge of circles)
-    let arrow_start_pos = start_pos + direction * NODE_SIZE;
-    let arrow_end_pos = end_pos - direction * NODE_SIZE;
+    world.get::<&usize>(entity).ok().map(|node_id| *node_id)
+}
 
-    // Draw arrowhead
-    let arrowhead_size = 15.0;
-    // To find the perpendicular vector [x,y] to [i,j] we need the dot product to be zero
-    // Thus u dot v = 0 => xi + yj = 0. From inspection we can see x = j and y = -i would suffice.
-    let perpendicular = Vec2::new(direction.y, -direction.x);
+// The ids of the nodes that stay pinned once any drag ends, leaving out a node only held by a drag.
+pub(crate) fn pinned_node_ids(world: &World) -> HashSet<usize> {
+    let dragged_node = current_view(world).dragged_node;
 
-    let arrowhead_left_end_pos =
-        arrow_end_pos - direction * arrowhead_size + perpendicular * (arrowhead_size * 0.5);
-    let arrowhead_right_end_pos =
-        arrow_end_pos - direction * arrowhead_size - perpendicular * (arrowhead_size * 0.5);
-    (
-        arrow_start_pos,
-        arrow_end_pos,
-        arrowhead_left_end_pos,
-        arrowhead_right_end_pos,
-    )
+    world
+        .query::<(&usize, Option<&Pinned>)>()
+        .iter()
+        .filter(|&(entity, (_, pinned))| match dragged_node {
+            Some(dragged_node) if dragged_node.entity == entity => dragged_node.is_pinned_after,
+            _ => pinned.is_some(),
+        })
+        .map(|(_, (&node_id, _))| node_id)
+        .collect()
 }
 
-fn draw_arrow_line(
-    start_pos: Vec2,
-    end_pos: Vec2,
-    color: Color,
-    thickness: f32,
-    is_directed: bool,
-) {
-    let (arrow_start_pos, arrow_end_pos, arrowhead_left_end_pos, arrowhead_right_end_pos) =
-        calculate_arrow_positions(start_pos, end_pos);
-
-    render_arrow(
-        color,
-        thickness,
-        arrow_start_pos,
-        arrow_end_pos,
-        arrowhead_left_end_pos,
-        arrowhead_right_end_pos,
-        is_directed,
-    );
+// Takes the selection events since the last call, oldest first.
+pub(crate) fn drain_selection_events(world: &mut World) -> Vec<SelectionEvent> {
+    world
+        .query_mut::<&mut ScrollableView>()
+        .into_iter()
+        .flat_map(|(_, view)| std::mem::take(&mut view.selection_events))
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// A plain click selects just the clicked node, keeping the selection as it is if the node is
+// already part of it. Holding shift adds the node to the selection, or takes it out again.
+fn select_node(world: &mut World, view: &mut ScrollableView, entity: Entity, is_additive: bool) {
+    let is_selected = world.satisfies::<&Selected>(entity).unwrap_or(false);
 
-    #[test]
-    fn should_calculate_arrow_positions() {
-        let (start_pos, end_pos) = (Vec2 { x: 0.0, y: 0.0 }, Vec2 { x: 100.0, y: 0.0 });
+    if is_additive {
+        set_selected(world, view, entity, !is_selected);
+    } else if !is_selected {
+        clear_selection(world, view);
+        set_selected(world, view, entity, true);
+    }
+}
 
-        let (arrow_start_pos, arrow_end_pos, arrowhead_left_end_pos, arrowhead_right_end_pos) =
-            calculate_arrow_positions(start_pos, end_pos);
+fn clear_selection(world: &mut World, view: &mut ScrollableView) {
+    let selected_entities: Vec<Entity> = world
+        .query::<&Selected>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect();
 
-        assert_eq!(arrow_start_pos, Vec2 { x: 15.0, y: 0.0 });
-        assert_eq!(arrow_end_pos, Vec2 { x: 85.0, y: 0.0 });
-        assert_eq!(arrowhead_left_end_pos, Vec2 { x: 70.0, y: -7.5 });
-        assert_eq!(arrowhead_right_end_pos, Vec2 { x: 70.0, y: 7.5 });
+    for entity in selected_entities {
+        set_selected(world, view, entity, false);
+    }
+}
+
+fn set_selected(world: &mut World, view: &mut ScrollableView, entity: Entity, is_selected: bool) {
+    let Ok(was_selected) = world.satisfies::<&Selected>(entity) else {
+        return;
+    };
+
+    if was_selected == is_selected {
+        return;
+    }
+
+    let (id, label) = match world.query_one_mut::<(&usize, &String)>(entity) {
+        Ok((&node_id, label)) => (NodeId(node_id), label.clone()),
+        Err(_) => return,
+    };
+
+    if is_selected {
+        world.insert_one(entity, Selected).unwrap();
+        view.push_selection_event(SelectionEvent::Selected { id, label });
+    } else {
+        world.remove_one::<Selected>(entity).unwrap();
+        view.push_selection_event(SelectionEvent::Deselected { id, label });
+    }
+}
+
+// Nodes whose centre lies in the box with the given opposite corners, in world units.
+fn nodes_in_box(world: &World, corner: Vec2, opposite_corner: Vec2) -> Vec<Entity> {
+    let (min, max) = (corner.min(opposite_corner), corner.max(opposite_corner));
+
+    world
+        .query::<(&Position, &usize)>()
+        .iter()
+        .filter(|(_, (position, _))| {
+            let centre = Vec2::new(position.x, position.y);
+
+            centre.cmpge(min).all() && centre.cmple(max).all()
+        })
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+// The smallest box around every node, in world units.
+fn graph_bounds(world: &World) -> Option<(Vec2, Vec2)> {
+    world
+        .query::<(&Position, &Size)>()
+        .iter()
+        .map(|(_, (position, size))| {
+            let centre = Vec2::new(position.x, position.y);
+
+            (centre - size.radius, centre + size.radius)
+        })
+        .reduce(|(min, max), (node_min, node_max)| (min.min(node_min), max.max(node_max)))
+}
+
+pub(crate) fn graph_centroid(world: &World) -> Option<Vec2> {
+    let mut query = world.query::<&Position>();
+    let (sum, count) = query
+        .iter()
+        .fold((Vec2::ZERO, 0), |(sum, count), (_, position)| {
+            (sum + Vec2::new(position.x, position.y), count + 1)
+        });
+
+    (count > 0).then(|| sum / count as f32)
+}
+
+// The topmost node at a world position. Nodes are drawn in query order, so the last one hit is on
+// top.
+fn node_at(world: &World, world_pos: Vec2) -> Option<Entity> {
+    world
+        .query::<(&Position, &NodeStyle)>()
+        .iter()
+        .filter(|(_, (position, style))| {
+            is_inside_node(world_pos - Vec2::new(position.x, position.y), style)
+        })
+        .last()
+        .map(|(entity, _)| entity)
+}
+
+// The topmost edge within `tolerance` world units of a world position.
+fn edge_at(world: &World, world_pos: Vec2, tolerance: f32) -> Option<Entity> {
+    let node_data = node_circles_by_id(world);
+
+    edges_by_entity(world)
+        .into_iter()
+        .rev()
+        .find(|(_, edge)| {
+            let Some(path) = calculate_edge_world_path(&node_data, edge) else {
+                return false;
+            };
+            let reach = edge_thickness(&edge.style, edge.weight) / 2.0 + tolerance;
+
+            path.windows(2)
+                .any(|segment| distance_to_segment(world_pos, segment[0], segment[1]) <= reach)
+        })
+        .map(|(entity, _)| entity)
+}
+
+fn is_inside_node(offset_from_centre: Vec2, style: &NodeStyle) -> bool {
+    match style.shape {
+        NodeShape::Circle => offset_from_centre.length() <= style.radius,
+        NodeShape::Square | NodeShape::RoundedRect => {
+            offset_from_centre.abs().max_element() <= style.radius
+        }
+        NodeShape::Diamond => {
+            offset_from_centre.x.abs() + offset_from_centre.y.abs() <= style.radius
+        }
+    }
+}
+
+// Nodes are pinned while they are held so the physics leaves them under the cursor. Grabbing the
+// same node twice in quick succession toggles whether it stays pinned once let go of.
+fn grab_node(
+    world: &mut World,
+    view: &mut ScrollableView,
+    entity: Entity,
+    mouse_pos: Vec2,
+    time: f64,
+) {
+    let node_pos = match world.get::<&Position>(entity) {
+        Ok(position) => Vec2::new(position.x, position.y),
+        Err(_) => return,
+    };
+
+    let is_pinned = world.satisfies::<&Pinned>(entity).unwrap_or(false);
+    let is_double_click = view.last_click.is_some_and(|(clicked_entity, clicked_at)| {
+        clicked_entity == entity && time - clicked_at < DOUBLE_CLICK_TIME
+    });
+
+    view.last_click = if is_double_click {
+        None
+    } else {
+        Some((entity, time))
+    };
+    view.dragged_node = Some(DraggedNode {
+        entity,
+        grab_offset: node_pos - mouse_pos,
+        is_pinned_after: is_pinned != is_double_click,
+    });
+
+    world.insert_one(entity, Pinned).unwrap();
+}
+
+fn move_dragged_node(world: &mut World, dragged_node: DraggedNode, mouse_pos: Vec2) {
+    let Ok((position, velocity)) =
+        world.query_one_mut::<(&mut Position, &mut Velocity)>(dragged_node.entity)
+    else {
+        return;
+    };
+
+    let node_pos = mouse_pos + dragged_node.grab_offset;
+
+    *position = Position {
+        x: node_pos.x,
+        y: node_pos.y,
+    };
+    *velocity = Velocity { x: 0.0, y: 0.0 };
+}
+
+fn release_node(world: &mut World, dragged_node: DraggedNode) {
+    if !dragged_node.is_pinned_after {
+        let _ = world.remove_one::<Pinned>(dragged_node.entity);
+    }
+}
+
+fn render_edges(world: &World, highlight: Option<&Highlight>) {
+    let view = current_view(world);
+    let node_data = node_circles_by_id(world);
+    let node_circles: Vec<(Vec2, f32)> = node_data.values().copied().collect();
+
+    for (entity, edge) in edges_by_entity(world) {
+        let Some(path) = calculate_edge_world_path(&node_data, &edge) else {
+            continue;
+        };
+        let path: Vec<Vec2> = path.into_iter().map(|pos| view.to_screen(pos)).collect();
+
+        let is_dimmed = highlight.is_some_and(|highlight| !highlight.edges.contains(&entity));
+        let is_hovered = view.hovered == Some(Hovered::Edge(entity));
+        let style = EdgeStyle {
+            color: if is_dimmed {
+                dim(edge.style.color)
+            } else {
+                edge.style.color
+            },
+            ..edge.style
+        };
+        let mut thickness = edge_thickness(&edge.style, edge.weight) * view.scale;
+
+        if is_hovered {
+            thickness += HIGHLIGHT_WIDTH * view.scale;
+        }
+
+        draw_edge(&path, &style, thickness, edge.is_directed, view.scale);
+
+        if let Some(label) = &edge.label {
+            draw_edge_label(
+                label,
+                &edge,
+                &node_data,
+                &node_circles,
+                &view,
+                if is_dimmed { dim(WHITE) } else { WHITE },
+            );
+        }
+    }
+}
+
+// The path of an edge in world units, if both its nodes are in the world.
+fn calculate_edge_world_path(
+    node_data: &HashMap<usize, (Vec2, f32)>,
+    edge: &Edge,
+) -> Option<Vec<Vec2>> {
+    let source = *node_data.get(&edge.source_node_id)?;
+    let destination = *node_data.get(&edge.destination_node_id)?;
+
+    Some(calculate_edge_path(
+        source,
+        destination,
+        edge.source_node_id == edge.destination_node_id,
+        edge.lane,
+    ))
+}
+
+fn render_nodes(world: &World, highlight: Option<&Highlight>) {
+    let view = current_view(world);
+
+    for (entity, (&node_id, position, label, style, pinned, selected)) in &mut world.query::<(
+        &usize,
+        &Position,
+        &String,
+        &NodeStyle,
+        Option<&Pinned>,
+        Option<&Selected>,
+    )>() {
+        let position = view.world_pos_to_screen_pos(position);
+        let is_dimmed = highlight.is_some_and(|highlight| !highlight.node_ids.contains(&node_id));
+        let style = if is_dimmed {
+            NodeStyle {
+                fill: dim(style.fill),
+                stroke: dim(style.stroke),
+                label_color: dim(style.label_color),
+                ..*style
+            }
+        } else {
+            *style
+        };
+        let scaled_style = NodeStyle {
+            radius: style.radius * view.scale,
+            stroke_width: style.stroke_width * view.scale,
+            ..style
+        };
+
+        // Outlines are drawn as larger copies of the node behind it, the selection inside the
+        // hover outline so both show.
+        if view.hovered == Some(Hovered::Node(entity)) {
+            draw_node_shape(
+                Vec2::new(position.x, position.y),
+                &NodeStyle {
+                    fill: YELLOW,
+                    radius: scaled_style.radius + 2.0 * HIGHLIGHT_WIDTH * view.scale,
+                    stroke_width: 0.0,
+                    ..scaled_style
+                },
+            );
+        }
+
+        if selected.is_some() {
+            draw_node_shape(
+                Vec2::new(position.x, position.y),
+                &NodeStyle {
+                    fill: SKYBLUE,
+                    radius: scaled_style.radius + HIGHLIGHT_WIDTH * view.scale,
+                    stroke_width: 0.0,
+                    ..scaled_style
+                },
+            );
+        }
+
+        draw_node_shape(Vec2::new(position.x, position.y), &scaled_style);
+
+        // Nodes only held by a drag are not marked as pinned.
+        let is_pinned = match view.dragged_node {
+            Some(dragged_node) if dragged_node.entity == entity => dragged_node.is_pinned_after,
+            _ => pinned.is_some(),
+        };
+
+        if is_pinned {
+            let marker_pos = Vec2::new(position.x, position.y)
+                + Vec2::new(1.0, -1.0) * (scaled_style.radius * 0.7);
+
+            draw_circle(
+                marker_pos.x,
+                marker_pos.y,
+                PIN_MARKER_RADIUS * view.scale,
+                style.label_color,
+            );
+        }
+
+        let font_size = style.font_size as f32 * view.scale;
+
+        if font_size < MIN_FONT_SIZE {
+            continue;
+        }
+
+        let center_of_text = get_text_center(label, None, font_size.round() as u16, 1.0, 0.0);
+
+        draw_text(
+            label,
+            position.x - center_of_text.x,
+            position.y - center_of_text.y / 2.0,
+            font_size.round(),
+            style.label_color,
+        );
+    }
+}
+
+fn current_view(world: &World) -> ScrollableView {
+    let mut scrollable_view_query = world.query::<&ScrollableView>();
+    let (_, scrollable_view) = scrollable_view_query
+        .iter()
+        .next()
+        .expect("No scrollable view found");
+
+    scrollable_view.clone()
+}
+
+// Outlines are drawn inside the shape so that its size always matches the radius.
+fn draw_node_shape(centre: Vec2, style: &NodeStyle) {
+    let radius = style.radius;
+    let has_stroke = style.stroke_width > 0.0;
+
+    match style.shape {
+        NodeShape::Circle => {
+            draw_circle(centre.x, centre.y, radius, style.fill);
+
+            if has_stroke {
+                draw_circle_lines(
+                    centre.x,
+                    centre.y,
+                    radius - style.stroke_width,
+                    style.stroke_width,
+                    style.stroke,
+                );
+            }
+        }
+        NodeShape::Square => {
+            let corner = centre - Vec2::splat(radius);
+
+            draw_rectangle(corner.x, corner.y, 2.0 * radius, 2.0 * radius, style.fill);
+
+            if has_stroke {
+                draw_rectangle_lines(
+                    corner.x,
+                    corner.y,
+                    2.0 * radius,
+                    2.0 * radius,
+                    style.stroke_width,
+                    style.stroke,
+                );
+            }
+        }
+        NodeShape::Diamond => {
+            draw_poly(centre.x, centre.y, 4, radius, 0.0, style.fill);
+
+            if has_stroke {
+                draw_poly_lines(
+                    centre.x,
+                    centre.y,
+                    4,
+                    radius - style.stroke_width,
+                    0.0,
+                    style.stroke_width,
+                    style.stroke,
+                );
+            }
+        }
+        NodeShape::RoundedRect => draw_rounded_rect(centre, style),
+    }
+}
+
+// A square with its corners replaced by quarter circles, built from two overlapping rectangles and
+// a circle in each corner.
+fn draw_rounded_rect(centre: Vec2, style: &NodeStyle) {
+    let radius = style.radius;
+    let corner_radius = radius * ROUNDED_CORNER_RATIO;
+    let inner = radius - corner_radius;
+
+    draw_rectangle(
+        centre.x - radius,
+        centre.y - inner,
+        2.0 * radius,
+        2.0 * inner,
+        style.fill,
+    );
+    draw_rectangle(
+        centre.x - inner,
+        centre.y - radius,
+        2.0 * inner,
+        2.0 * radius,
+        style.fill,
+    );
+
+    // Corners in clockwise order from the bottom right, matching the angles used by `draw_arc`.
+    let corners = [
+        Vec2::new(inner, inner),
+        Vec2::new(-inner, inner),
+        Vec2::new(-inner, -inner),
+        Vec2::new(inner, -inner),
+    ];
+
+    for corner in corners {
+        draw_circle(
+            centre.x + corner.x,
+            centre.y + corner.y,
+            corner_radius,
+            style.fill,
+        );
+    }
+
+    if style.stroke_width <= 0.0 {
+        return;
+    }
+
+    let arc_radius = corner_radius - style.stroke_width;
+
+    for (index, corner) in corners.iter().enumerate() {
+        draw_arc(
+            centre.x + corner.x,
+            centre.y + corner.y,
+            8,
+            arc_radius,
+            index as f32 * 90.0,
+            style.stroke_width,
+            90.0,
+            style.stroke,
+        );
+    }
+
+    let edge_offset = radius - style.stroke_width / 2.0;
+
+    draw_line(
+        centre.x - inner,
+        centre.y - edge_offset,
+        centre.x + inner,
+        centre.y - edge_offset,
+        style.stroke_width,
+        style.stroke,
+    );
+    draw_line(
+        centre.x - inner,
+        centre.y + edge_offset,
+        centre.x + inner,
+        centre.y + edge_offset,
+        style.stroke_width,
+        style.stroke,
+    );
+    draw_line(
+        centre.x - edge_offset,
+        centre.y - inner,
+        centre.x - edge_offset,
+        centre.y + inner,
+        style.stroke_width,
+        style.stroke,
+    );
+    draw_line(
+        centre.x + edge_offset,
+        centre.y - inner,
+        centre.x + edge_offset,
+        centre.y + inner,
+        style.stroke_width,
+        style.stroke,
+    );
+}
+
+// The position and radius of every node in world units, by node id.
+fn node_circles_by_id(world: &World) -> HashMap<usize, (Vec2, f32)> {
+    world
+        .query::<(&Position, &Size, &usize)>()
+        .iter()
+        .map(|(_, (position, size, &node_id))| {
+            (node_id, (Vec2::new(position.x, position.y), size.radius))
+        })
+        .collect()
+}
+
+fn edge_thickness(style: &EdgeStyle, weight: f32) -> f32 {
+    (style.width * weight).clamp(1.0, MAX_EDGE_THICKNESS.max(style.width))
+}
+
+fn calculate_arrow_positions(
+    (start_pos, start_radius): (Vec2, f32),
+    (end_pos, end_radius): (Vec2, f32),
+) -> (Vec2, Vec2) {
+    // Calculate direction vector
+    let direction = (end_pos - start_pos).normalize();
+
+    // Calculate arrow start and end points (edge of circles)
+    let arrow_start_pos = start_pos + direction * start_radius;
+    let arrow_end_pos = end_pos - direction * end_radius;
+
+    (arrow_start_pos, arrow_end_pos)
+}
+
+fn calculate_arrowhead_positions(tip: Vec2, direction: Vec2, size: f32) -> (Vec2, Vec2) {
+    // To find the perpendicular vector [x,y] to [i,j] we need the dot product to be zero
+    // Thus u dot v = 0 => xi + yj = 0. From inspection we can see x = j and y = -i would suffice.
+    let perpendicular = Vec2::new(direction.y, -direction.x);
+
+    let arrowhead_left_end_pos = tip - direction * size + perpendicular * (size * 0.5);
+    let arrowhead_right_end_pos = tip - direction * size - perpendicular * (size * 0.5);
+
+    (arrowhead_left_end_pos, arrowhead_right_end_pos)
+}
+
+// A quadratic Bézier curve whose middle sits `bend` to the side of the straight line between the
+// nodes, clipped to the edge of both node circles. Returns the start, control and end points.
+fn calculate_curve_positions(
+    (start_pos, start_radius): (Vec2, f32),
+    (end_pos, end_radius): (Vec2, f32),
+    bend: f32,
+) -> (Vec2, Vec2, Vec2) {
+    let control_pos = calculate_control_position(start_pos, end_pos, bend);
+
+    let curve_start_pos = start_pos + (control_pos - start_pos).normalize() * start_radius;
+    let curve_end_pos = end_pos - (end_pos - control_pos).normalize() * end_radius;
+
+    (curve_start_pos, control_pos, curve_end_pos)
+}
+
+fn calculate_control_position(start_pos: Vec2, end_pos: Vec2, bend: f32) -> Vec2 {
+    let direction = (end_pos - start_pos).normalize();
+    let perpendicular = Vec2::new(direction.y, -direction.x);
+
+    // The midpoint of a quadratic Bézier lies halfway between the chord and the control point.
+    (start_pos + end_pos) / 2.0 + perpendicular * (2.0 * bend)
+}
+
+fn quadratic_bezier(start_pos: Vec2, control_pos: Vec2, end_pos: Vec2, t: f32) -> Vec2 {
+    start_pos * (1.0 - t).powi(2) + control_pos * (2.0 * (1.0 - t) * t) + end_pos * t.powi(2)
+}
+
+// A small circle overlapping the node, rotated further round for every extra loop on the same node.
+// Returns the centre of the loop and where and in which direction it re-enters the node.
+fn calculate_self_loop_positions(
+    node_pos: Vec2,
+    node_radius: f32,
+    lane: f32,
+) -> (Vec2, Vec2, Vec2) {
+    let angle = -std::f32::consts::FRAC_PI_2 + lane * 0.9;
+    let axis = Vec2::new(angle.cos(), angle.sin());
+    let distance_between_centres = node_radius + SELF_LOOP_RADIUS * 0.8;
+    let loop_centre = node_pos + axis * distance_between_centres;
+
+    // Intersection of the node circle and the loop circle
+    let along_axis = (distance_between_centres.powi(2) + node_radius.powi(2)
+        - SELF_LOOP_RADIUS.powi(2))
+        / (2.0 * distance_between_centres);
+    let across_axis = (node_radius.powi(2) - along_axis.powi(2)).sqrt();
+    let perpendicular = Vec2::new(axis.y, -axis.x);
+    let arrow_tip = node_pos + axis * along_axis + perpendicular * across_axis;
+
+    let radius_at_tip = arrow_tip - loop_centre;
+    let tangent = Vec2::new(-radius_at_tip.y, radius_at_tip.x).normalize();
+    let direction = if tangent.dot(node_pos - arrow_tip) > 0.0 {
+        tangent
+    } else {
+        -tangent
+    };
+
+    (loop_centre, arrow_tip, direction)
+}
+
+// The loop as seen from outside the node, from where it leaves the node round to where it
+// re-enters.
+fn calculate_self_loop_path(node_pos: Vec2, node_radius: f32, lane: f32) -> Vec<Vec2> {
+    let (loop_centre, arrow_tip, _) = calculate_self_loop_positions(node_pos, node_radius, lane);
+
+    // The loop leaves the node at the reflection of where it re-enters, across its axis.
+    let axis = (loop_centre - node_pos).normalize();
+    let tip_offset = arrow_tip - node_pos;
+    let exit_pos = node_pos + axis * (2.0 * tip_offset.dot(axis)) - tip_offset;
+
+    let start_angle = (exit_pos - loop_centre).to_angle();
+    let mut sweep = ((arrow_tip - loop_centre).to_angle() - start_angle).rem_euclid(TAU);
+
+    // Of the two arcs between the intersections, the longer one lies outside the node.
+    if sweep < PI {
+        sweep -= TAU;
+    }
+
+    (0..=CURVE_SEGMENTS)
+        .map(|segment| {
+            let angle = start_angle + sweep * segment as f32 / CURVE_SEGMENTS as f32;
+
+            loop_centre + Vec2::from_angle(angle) * SELF_LOOP_RADIUS
+        })
+        .collect()
+}
+
+// The points an edge is drawn through, from the edge of its source node to the edge of its
+// destination node.
+fn calculate_edge_path(
+    start: (Vec2, f32),
+    end: (Vec2, f32),
+    is_self_loop: bool,
+    lane: f32,
+) -> Vec<Vec2> {
+    if is_self_loop {
+        return calculate_self_loop_path(start.0, start.1, lane);
+    }
+
+    if lane == 0.0 {
+        let (arrow_start_pos, arrow_end_pos) = calculate_arrow_positions(start, end);
+
+        return vec![arrow_start_pos, arrow_end_pos];
+    }
+
+    let (curve_start_pos, control_pos, curve_end_pos) =
+        calculate_curve_positions(start, end, lane * LANE_SPACING);
+
+    (0..=CURVE_SEGMENTS)
+        .map(|segment| {
+            let t = segment as f32 / CURVE_SEGMENTS as f32;
+
+            quadratic_bezier(curve_start_pos, control_pos, curve_end_pos, t)
+        })
+        .collect()
+}
+
+// Splits a path into the stretches drawn for a dashed line, carrying the pattern on across the
+// corners of the path.
+fn calculate_dashes(path: &[Vec2], dash: f32, gap: f32) -> Vec<(Vec2, Vec2)> {
+    let (Some(&first_pos), Some(&last_pos)) = (path.first(), path.last()) else {
+        return Vec::new();
+    };
+
+    let mut dashes = Vec::new();
+    let mut is_dash = true;
+    let mut remaining = dash;
+    let mut dash_start_pos = first_pos;
+
+    for segment in path.windows(2) {
+        let (mut from_pos, to_pos) = (segment[0], segment[1]);
+        let mut length = from_pos.distance(to_pos);
+
+        while length > remaining {
+            let pos = from_pos.lerp(to_pos, remaining / length);
+
+            if is_dash {
+                dashes.push((dash_start_pos, pos));
+            } else {
+                dash_start_pos = pos;
+            }
+
+            is_dash = !is_dash;
+            length -= remaining;
+            from_pos = pos;
+            remaining = if is_dash { dash } else { gap };
+        }
+
+        remaining -= length;
+    }
+
+    if is_dash {
+        dashes.push((dash_start_pos, last_pos));
+    }
+
+    dashes
+}
+
+// Draws an edge along a path in screen space. `scale` sizes the arrowheads to match the zoom.
+fn draw_edge(path: &[Vec2], style: &EdgeStyle, thickness: f32, is_directed: bool, scale: f32) {
+    match style.pattern {
+        LinePattern::Solid => {
+            for segment in path.windows(2) {
+                draw_line(
+                    segment[0].x,
+                    segment[0].y,
+                    segment[1].x,
+                    segment[1].y,
+                    thickness,
+                    style.color,
+                );
+            }
+        }
+        LinePattern::Dashed => {
+            for (from_pos, to_pos) in
+                calculate_dashes(path, DASH_LENGTH * thickness, DASH_GAP * thickness)
+            {
+                draw_line(
+                    from_pos.x,
+                    from_pos.y,
+                    to_pos.x,
+                    to_pos.y,
+                    thickness,
+                    style.color,
+                );
+            }
+        }
+        LinePattern::Dotted => {
+            for (dot_pos, _) in calculate_dashes(path, 0.0, DOT_SPACING * thickness) {
+                draw_circle(dot_pos.x, dot_pos.y, thickness / 2.0, style.color);
+            }
+        }
+    }
+
+    let [first_pos, second_pos, ..] = path[..] else {
+        return;
+    };
+    let [.., second_last_pos, last_pos] = path[..] else {
+        return;
+    };
+
+    if style.both_ends {
+        let direction = (first_pos - second_pos).normalize();
+
+        draw_arrowhead(
+            first_pos,
+            direction,
+            style,
+            thickness,
+            ARROWHEAD_SIZE * scale,
+        );
+    }
+
+    if is_directed || style.both_ends {
+        let direction = (last_pos - second_last_pos).normalize();
+
+        draw_arrowhead(
+            last_pos,
+            direction,
+            style,
+            thickness,
+            ARROWHEAD_SIZE * scale,
+        );
+    }
+}
+
+// The point at `t` along an edge drawn between two node centres, and the direction of the edge
+// there.
+fn calculate_edge_point(start_pos: Vec2, end_pos: Vec2, lane: f32, t: f32) -> (Vec2, Vec2) {
+    if lane == 0.0 {
+        return (
+            start_pos.lerp(end_pos, t),
+            (end_pos - start_pos).normalize(),
+        );
+    }
+
+    let control_pos = calculate_control_position(start_pos, end_pos, lane * LANE_SPACING);
+    let tangent = (control_pos - start_pos) * (1.0 - t) + (end_pos - control_pos) * t;
+
+    (
+        quadratic_bezier(start_pos, control_pos, end_pos, t),
+        tangent.normalize(),
+    )
+}
+
+// Places a label of the given size beside an edge, trying positions from the middle outwards until
+// it no longer overlaps any node circle. The label runs along the edge but is flipped so it never
+// reads upside down. Returns the centre of the label and the direction its text runs in.
+fn calculate_edge_label_placement(
+    start_pos: Vec2,
+    end_pos: Vec2,
+    lane: f32,
+    label_size: Vec2,
+    node_circles: &[(Vec2, f32)],
+) -> (Vec2, Vec2) {
+    let place = |t: f32| {
+        let (point, tangent) = calculate_edge_point(start_pos, end_pos, lane, t);
+        let direction = if tangent.x < 0.0 { -tangent } else { tangent };
+        let above = Vec2::new(direction.y, -direction.x);
+
+        (
+            point + above * (label_size.y / 2.0 + EDGE_LABEL_GAP),
+            direction,
+        )
+    };
+
+    EDGE_LABEL_ANCHORS
+        .iter()
+        .map(|&t| place(t))
+        .find(|&(centre, direction)| {
+            let half_extent = direction * (label_size.x / 2.0);
+
+            node_circles.iter().all(|&(node_pos, radius)| {
+                distance_to_segment(node_pos, centre - half_extent, centre + half_extent)
+                    > radius + label_size.y / 2.0
+            })
+        })
+        .unwrap_or_else(|| place(0.5))
+}
+
+fn distance_to_segment(point: Vec2, segment_start: Vec2, segment_end: Vec2) -> f32 {
+    let segment = segment_end - segment_start;
+    let length_squared = segment.length_squared();
+
+    if length_squared == 0.0 {
+        return point.distance(segment_start);
+    }
+
+    let t = ((point - segment_start).dot(segment) / length_squared).clamp(0.0, 1.0);
+
+    point.distance(segment_start + segment * t)
+}
+
+fn draw_edge_label(
+    label: &str,
+    edge: &Edge,
+    node_data: &HashMap<usize, (Vec2, f32)>,
+    node_circles: &[(Vec2, f32)],
+    view: &ScrollableView,
+    color: Color,
+) {
+    let (start_pos, start_radius) = node_data[&edge.source_node_id];
+    let (end_pos, _) = node_data[&edge.destination_node_id];
+    let lane = edge.lane;
+
+    let font_size = EDGE_LABEL_FONT_SIZE as f32 * view.scale;
+
+    if font_size < MIN_FONT_SIZE {
+        return;
+    }
+
+    // The label is placed in world units, so it keeps its place along the edge when zooming.
+    let dimensions = measure_text(label, None, EDGE_LABEL_FONT_SIZE, 1.0);
+    let label_size = Vec2::new(dimensions.width, dimensions.height);
+
+    let (centre, direction) = if edge.source_node_id == edge.destination_node_id {
+        // Loops are too small to follow, so the label sits unrotated just beyond the loop.
+        let (loop_centre, _, _) = calculate_self_loop_positions(start_pos, start_radius, lane);
+        let outwards = (loop_centre - start_pos).normalize();
+        let clearance = SELF_LOOP_RADIUS + EDGE_LABEL_GAP + label_size.length() / 2.0;
+
+        (loop_centre + outwards * clearance, Vec2::X)
+    } else {
+        calculate_edge_label_placement(start_pos, end_pos, lane, label_size, node_circles)
+    };
+
+    // Text is drawn from the left end of its baseline.
+    let below = Vec2::new(-direction.y, direction.x);
+    let label_size = label_size * view.scale;
+    let origin =
+        view.to_screen(centre) - direction * (label_size.x / 2.0) + below * (label_size.y / 2.0);
+
+    draw_text_ex(
+        label,
+        origin.x,
+        origin.y,
+        TextParams {
+            font_size: font_size.round() as u16,
+            rotation: direction.y.atan2(direction.x),
+            color,
+            ..Default::default()
+        },
+    );
+}
+
+fn draw_arrowhead(tip: Vec2, direction: Vec2, style: &EdgeStyle, thickness: f32, size: f32) {
+    let color = style.color;
+
+    match style.arrowhead {
+        ArrowHead::None => {}
+        ArrowHead::Open => {
+            let (arrowhead_left, arrowhead_right) =
+                calculate_arrowhead_positions(tip, direction, size);
+
+            draw_line(
+                tip.x,
+                tip.y,
+                arrowhead_left.x,
+                arrowhead_left.y,
+                thickness,
+                color,
+            );
+            draw_line(
+                tip.x,
+                tip.y,
+                arrowhead_right.x,
+                arrowhead_right.y,
+                thickness,
+                color,
+            );
+        }
+        ArrowHead::Filled => {
+            let (arrowhead_left, arrowhead_right) =
+                calculate_arrowhead_positions(tip, direction, size);
+
+            draw_triangle(tip, arrowhead_left, arrowhead_right, color);
+        }
+        ArrowHead::Diamond => {
+            let perpendicular = Vec2::new(direction.y, -direction.x);
+            let back = tip - direction * size;
+            let middle = tip - direction * (size * 0.5);
+            let left = middle + perpendicular * (size * 0.35);
+            let right = middle - perpendicular * (size * 0.35);
+
+            draw_triangle(tip, left, right, color);
+            draw_triangle(back, left, right, color);
+        }
+        ArrowHead::Circle => {
+            let radius = size / 3.0;
+            let centre = tip - direction * radius;
+
+            draw_circle(centre.x, centre.y, radius, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{spawn_graph, Graph};
+
+    const NODE_SIZE: f32 = 15.0;
+
+    #[test]
+    fn should_calculate_arrow_positions() {
+        let (start_pos, end_pos) = (Vec2 { x: 0.0, y: 0.0 }, Vec2 { x: 100.0, y: 0.0 });
+
+        let (arrow_start_pos, arrow_end_pos) =
+            calculate_arrow_positions((start_pos, NODE_SIZE), (end_pos, NODE_SIZE));
+        let (arrowhead_left_end_pos, arrowhead_right_end_pos) =
+            calculate_arrowhead_positions(arrow_end_pos, Vec2::X, ARROWHEAD_SIZE);
+
+        assert_eq!(arrow_start_pos, Vec2 { x: 15.0, y: 0.0 });
+        assert_eq!(arrow_end_pos, Vec2 { x: 85.0, y: 0.0 });
+        assert_eq!(arrowhead_left_end_pos, Vec2 { x: 70.0, y: -7.5 });
+        assert_eq!(arrowhead_right_end_pos, Vec2 { x: 70.0, y: 7.5 });
+    }
+
+    #[test]
+    fn should_end_arrows_at_the_radius_of_each_node() {
+        let (start_pos, end_pos) = (Vec2 { x: 0.0, y: 0.0 }, Vec2 { x: 100.0, y: 0.0 });
+
+        let (arrow_start_pos, arrow_end_pos) =
+            calculate_arrow_positions((start_pos, 10.0), (end_pos, 20.0));
+
+        assert_eq!(arrow_start_pos, Vec2 { x: 10.0, y: 0.0 });
+        assert_eq!(arrow_end_pos, Vec2 { x: 80.0, y: 0.0 });
+    }
+
+    #[test]
+    fn should_bend_curves_away_from_the_straight_line() {
+        let (start_pos, end_pos) = (Vec2 { x: 0.0, y: 0.0 }, Vec2 { x: 100.0, y: 0.0 });
+
+        let (curve_start_pos, control_pos, curve_end_pos) =
+            calculate_curve_positions((start_pos, NODE_SIZE), (end_pos, NODE_SIZE), 30.0);
+        let middle_pos = quadratic_bezier(start_pos, control_pos, end_pos, 0.5);
+
+        assert_eq!(control_pos, Vec2 { x: 50.0, y: -60.0 });
+        assert_eq!(middle_pos, Vec2 { x: 50.0, y: -30.0 });
+        assert!((curve_start_pos.distance(start_pos) - NODE_SIZE).abs() < 1e-4);
+        assert!((curve_end_pos.distance(end_pos) - NODE_SIZE).abs() < 1e-4);
+    }
+
+    #[test]
+    fn should_place_self_loop_arrow_on_both_circles() {
+        let node_pos = Vec2 { x: 10.0, y: 10.0 };
+
+        for lane in [0.0, 1.0, 2.0] {
+            let (loop_centre, arrow_tip, direction) =
+                calculate_self_loop_positions(node_pos, NODE_SIZE, lane);
+
+            assert!((arrow_tip.distance(node_pos) - NODE_SIZE).abs() < 1e-3);
+            assert!((arrow_tip.distance(loop_centre) - SELF_LOOP_RADIUS).abs() < 1e-3);
+            assert!(direction.dot(node_pos - arrow_tip) > 0.0);
+        }
+    }
+
+    #[test]
+    fn should_place_edge_labels_above_the_middle_of_the_edge() {
+        let (start_pos, end_pos) = (Vec2 { x: 100.0, y: 0.0 }, Vec2 { x: 0.0, y: 0.0 });
+
+        let (centre, direction) = calculate_edge_label_placement(
+            start_pos,
+            end_pos,
+            0.0,
+            Vec2::new(20.0, 10.0),
+            &[(start_pos, NODE_SIZE), (end_pos, NODE_SIZE)],
+        );
+
+        assert_eq!(direction, Vec2::X);
+        assert_eq!(centre, Vec2::new(50.0, -5.0 - EDGE_LABEL_GAP));
+    }
+
+    #[test]
+    fn should_move_edge_labels_away_from_nodes() {
+        let (start_pos, end_pos) = (Vec2 { x: 0.0, y: 0.0 }, Vec2 { x: 200.0, y: 0.0 });
+        let label_size = Vec2::new(20.0, 10.0);
+        let in_the_middle = (Vec2::new(100.0, -10.0), NODE_SIZE);
+
+        let (centre, direction) =
+            calculate_edge_label_placement(start_pos, end_pos, 0.0, label_size, &[in_the_middle]);
+        let half_extent = direction * (label_size.x / 2.0);
+
+        assert_ne!(centre.x, 100.0);
+        assert!(
+            distance_to_segment(in_the_middle.0, centre - half_extent, centre + half_extent)
+                > NODE_SIZE + label_size.y / 2.0
+        );
+    }
+
+    #[test]
+    fn should_convert_between_world_and_screen_positions() {
+        let view = ScrollableView {
+            offset: Vec2::new(100.0, 50.0),
+            scale: 2.0,
+            ..ScrollableView::new()
+        };
+
+        let screen_pos = view.world_pos_to_screen_pos(&Position { x: 110.0, y: 40.0 });
+        let world_pos = view.screen_pos_to_world_pos(&screen_pos);
+
+        assert_eq!((screen_pos.x, screen_pos.y), (20.0, -20.0));
+        assert_eq!((world_pos.x, world_pos.y), (110.0, 40.0));
+    }
+
+    #[test]
+    fn should_place_the_view_inside_its_viewport() {
+        let mut view = ScrollableView {
+            offset: Vec2::new(100.0, 50.0),
+            scale: 2.0,
+            ..ScrollableView::new()
+        };
+
+        view.set_viewport(Rect::new(200.0, 10.0, 400.0, 300.0));
+
+        assert_eq!(
+            view.to_screen(Vec2::new(100.0, 50.0)),
+            Vec2::new(200.0, 10.0)
+        );
+        assert_eq!(
+            view.to_world(Vec2::new(220.0, 30.0)),
+            Vec2::new(110.0, 60.0)
+        );
+
+        view.centre_on(Vec2::new(500.0, 500.0), view.viewport().size());
+
+        assert_eq!(
+            view.to_screen(Vec2::new(500.0, 500.0)),
+            view.viewport().center()
+        );
+
+        let cursor = Vec2::new(300.0, 100.0);
+        let world_pos = view.to_world(cursor);
+
+        view.zoom_at(cursor, 2.0);
+
+        assert_eq!(view.to_screen(world_pos), cursor);
+    }
+
+    #[test]
+    fn should_zoom_around_the_cursor() {
+        let mut view = ScrollableView::new();
+        let cursor = Vec2::new(300.0, 200.0);
+
+        view.zoom_at(cursor, 2.0);
+
+        assert_eq!(view.scale, 2.0);
+        assert_eq!(view.to_screen(Vec2::new(300.0, 200.0)), cursor);
+
+        view.zoom_at(cursor, 1000.0);
+
+        assert_eq!(view.scale, MAX_SCALE);
+        assert!(view.to_screen(Vec2::new(300.0, 200.0)).distance(cursor) < 1e-3);
+
+        view.zoom_at(cursor, 0.0);
+
+        assert_eq!(view.scale, MIN_SCALE);
+    }
+
+    #[test]
+    fn should_only_accept_valid_zoom_limits() {
+        let mut view = ScrollableView::new();
+
+        assert!(view.set_zoom_limits(4.0, 0.5));
+        assert_eq!(view.zoom_limits(), (0.5, 4.0));
+        assert!(!view.set_zoom_limits(f32::NAN, 2.0));
+        assert!(!view.set_zoom_limits(0.0, 2.0));
+        assert_eq!(view.zoom_limits(), (0.5, 4.0));
+
+        assert!(view.set_zoom_limits(2.0, 3.0));
+        assert_eq!(view.scale, 2.0);
+
+        view.zoom_at(Vec2::ZERO, 10.0);
+
+        assert_eq!(view.scale, 3.0);
+    }
+
+    #[test]
+    fn should_fit_the_graph_to_the_view() {
+        let mut graph = Graph::new();
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_node("C");
+
+        let world = spawn_nodes(&graph);
+        let screen_size = Vec2::new(800.0, 600.0);
+        let mut view = ScrollableView::new();
+
+        view.fit_to_view(&world, screen_size);
+
+        let (min, max) = graph_bounds(&world).unwrap();
+        let (screen_min, screen_max) = (view.to_screen(min), view.to_screen(max));
+
+        assert!(screen_min.min_element() >= FIT_MARGIN - 1e-3);
+        assert!(screen_max.x <= screen_size.x - FIT_MARGIN + 1e-3);
+        assert!(screen_max.y <= screen_size.y - FIT_MARGIN + 1e-3);
+        assert!(
+            (screen_min.x - FIT_MARGIN).abs() < 1e-3 || (screen_min.y - FIT_MARGIN).abs() < 1e-3
+        );
+        assert!(((screen_min + screen_max) / 2.0).distance(screen_size / 2.0) < 1e-3);
+    }
+
+    #[test]
+    fn should_highlight_hovered_node_and_its_neighbours() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        let c = graph.add_node("C");
+        graph.add_directed_edge(a, b);
+        graph.add_directed_edge(b, c);
+
+        let world = spawn_nodes(&graph);
+        let entity_of = |id: NodeId| {
+            world
+                .query::<&usize>()
+                .iter()
+                .find(|(_, &node_id)| node_id == id.0)
+                .map(|(entity, _)| entity)
+                .unwrap()
+        };
+
+        let highlight = calculate_highlight(&world, Hovered::Node(entity_of(a)));
+
+        assert_eq!(highlight.node_ids, HashSet::from([a.0, b.0]));
+        assert_eq!(highlight.edges.len(), 1);
+
+        let highlight = calculate_highlight(&world, Hovered::Node(entity_of(b)));
+
+        assert_eq!(highlight.node_ids, HashSet::from([a.0, b.0, c.0]));
+        assert_eq!(highlight.edges.len(), 2);
+    }
+
+    #[test]
+    fn should_hover_edges_and_describe_them() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("Albania");
+        let b = graph.add_node("Nigeria");
+        let edge = graph.add_weighted_edge(a, b, 2.0).unwrap();
+        graph.set_edge_label(edge, Some("trade"));
+        graph.set_node_metadata(a, "capital", "Tirana");
+
+        let world = spawn_nodes(&graph);
+        let node_data = node_circles_by_id(&world);
+        let middle = (node_data[&a.0].0 + node_data[&b.0].0) / 2.0;
+        let edge_entity = edge_at(&world, middle, 1.0).unwrap();
+        let node_entity = node_at(&world, node_data[&a.0].0).unwrap();
+
+        assert_eq!(edge_at(&world, middle + Vec2::new(0.0, 200.0), 1.0), None);
+        assert_eq!(
+            tooltip_lines(&world, Hovered::Edge(edge_entity), &[]),
+            vec!["Albania → Nigeria", "trade", "weight: 2"]
+        );
+        assert_eq!(
+            tooltip_lines(&world, Hovered::Node(node_entity), &[]),
+            vec!["Albania", "capital: Tirana"]
+        );
+        assert_eq!(
+            tooltip_lines(
+                &world,
+                Hovered::Node(node_entity),
+                &["owner: trade".to_string()]
+            ),
+            vec!["Albania", "capital: Tirana", "owner: trade"]
+        );
+    }
+
+    #[test]
+    fn should_select_nodes_and_report_changes() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph.add_node("C");
+
+        let mut world = spawn_nodes(&graph);
+        let mut view = ScrollableView::new();
+        let node_data = node_circles_by_id(&world);
+        let entity_a = node_at(&world, node_data[&a.0].0).unwrap();
+        let entity_b = node_at(&world, node_data[&b.0].0).unwrap();
+        let selected = |world: &World| {
+            let mut labels: Vec<String> = world
+                .query::<(&String, &Selected)>()
+                .iter()
+                .map(|(_, (label, _))| label.clone())
+                .collect();
+            labels.sort();
+            labels
+        };
+
+        select_node(&mut world, &mut view, entity_a, false);
+        select_node(&mut world, &mut view, entity_b, true);
+
+        assert_eq!(selected(&world), vec!["A", "B"]);
+
+        select_node(&mut world, &mut view, entity_a, true);
+
+        assert_eq!(selected(&world), vec!["B"]);
+
+        select_node(&mut world, &mut view, entity_a, false);
+
+        assert_eq!(selected(&world), vec!["A"]);
+
+        let events = std::mem::take(&mut view.selection_events);
+        let selected_a = SelectionEvent::Selected {
+            id: a,
+            label: "A".to_string(),
+        };
+
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0], selected_a);
+        assert_eq!(
+            events[3],
+            SelectionEvent::Deselected {
+                id: b,
+                label: "B".to_string()
+            }
+        );
+
+        clear_selection(&mut world, &mut view);
+
+        assert!(selected(&world).is_empty());
+        assert_eq!(view.selection_events.len(), 1);
+    }
+
+    #[test]
+    fn should_drop_the_oldest_selection_events_once_full() {
+        let mut view = ScrollableView::new();
+        let event = |index: usize| SelectionEvent::Selected {
+            id: NodeId(index),
+            label: index.to_string(),
+        };
+
+        for index in 0..MAX_SELECTION_EVENTS + 10 {
+            view.push_selection_event(event(index));
+        }
+
+        assert_eq!(view.selection_events.len(), MAX_SELECTION_EVENTS);
+        assert_eq!(view.selection_events[0], event(10));
+    }
+
+    #[test]
+    fn should_find_nodes_inside_a_selection_box() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("A");
+        graph.add_node("B");
+
+        let world = spawn_nodes(&graph);
+        let a_pos = node_circles_by_id(&world)[&a.0].0;
+
+        let entities = nodes_in_box(&world, a_pos + Vec2::splat(10.0), a_pos - Vec2::splat(10.0));
+
+        assert_eq!(entities, vec![node_at(&world, a_pos).unwrap()]);
+    }
+
+    #[test]
+    fn should_keep_dragged_content_under_the_cursor() {
+        let mut view = ScrollableView::new();
+        view.zoom_at(Vec2::ZERO, 2.0);
+
+        let world_pos = Vec2::new(50.0, 50.0);
+        let grabbed_at = view.to_screen(world_pos);
+
+        view.drag_to(grabbed_at, 0.1);
+        view.drag_to(grabbed_at + Vec2::new(30.0, -10.0), 0.1);
+
+        assert_eq!(
+            view.to_screen(world_pos),
+            grabbed_at + Vec2::new(30.0, -10.0)
+        );
+    }
+
+    #[test]
+    fn should_only_fling_when_enabled() {
+        for fling in [false, true] {
+            let mut view = ScrollableView {
+                fling,
+                ..ScrollableView::new()
+            };
+
+            view.drag_to(Vec2::ZERO, 0.1);
+            view.drag_to(Vec2::new(100.0, 0.0), 0.1);
+            view.release();
+
+            let released_offset = view.offset;
+            view.coast(0.1);
+
+            assert_eq!(view.offset.x < released_offset.x, fling);
+
+            for _ in 0..100 {
+                view.coast(0.1);
+            }
+
+            assert_eq!(view.velocity, Vec2::ZERO);
+        }
+    }
+
+    fn spawn_nodes(graph: &Graph) -> World {
+        let mut world = spawn_graph(graph, Vec2::ZERO);
+        world.spawn((ScrollableView::new(),));
+        world
+    }
+
+    fn node_pos(world: &World, entity: Entity) -> Vec2 {
+        let position = world.get::<&Position>(entity).unwrap();
+        Vec2::new(position.x, position.y)
+    }
+
+    #[test]
+    fn should_outline_the_nodes_of_each_cluster() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("A");
+        let b = graph.add_node("B");
+        graph.add_node("C");
+        graph.set_node_cluster(a, Some("Backend"));
+        graph.set_node_cluster(b, Some("Backend"));
+
+        let world = spawn_nodes(&graph);
+        let node_data = node_circles_by_id(&world);
+        let bounds = cluster_bounds(&world);
+        let (min, max) = bounds["Backend"];
+        let extent = Vec2::splat(NODE_SIZE + CLUSTER_PADDING);
+
+        assert_eq!(bounds.len(), 1);
+        assert_eq!(
+            min,
+            (node_data[&a.0].0 - extent).min(node_data[&b.0].0 - extent)
+        );
+        assert_eq!(
+            max,
+            (node_data[&a.0].0 + extent).max(node_data[&b.0].0 + extent)
+        );
+    }
+
+    #[test]
+    fn should_hit_test_node_shapes() {
+        let mut graph = Graph::new();
+        let square = graph.add_node("Square");
+        graph.node_style_mut(square).unwrap().shape = NodeShape::Square;
+
+        let world = spawn_nodes(&graph);
+        let entity = node_at(&world, Vec2::new(600.0, 0.0)).unwrap();
+        let corner = Vec2::new(600.0 + 14.0, 14.0);
+
+        assert_eq!(node_at(&world, corner), Some(entity));
+        assert_eq!(node_at(&world, corner + Vec2::splat(2.0)), None);
+        assert!(is_inside_node(Vec2::new(10.0, 4.0), &NodeStyle::default()));
+        assert!(!is_inside_node(
+            Vec2::new(12.0, 12.0),
+            &NodeStyle::default()
+        ));
+    }
+
+    #[test]
+    fn should_drag_nodes_and_pin_them_on_double_click() {
+        let mut graph = Graph::new();
+        graph.add_node("A");
+
+        let mut world = spawn_nodes(&graph);
+        let mut view = ScrollableView::new();
+        let entity = node_at(&world, Vec2::new(600.0, 0.0)).unwrap();
+
+        grab_node(&mut world, &mut view, entity, Vec2::new(605.0, 0.0), 0.0);
+        let dragged_node = view.dragged_node.unwrap();
+        move_dragged_node(&mut world, dragged_node, Vec2::new(105.0, 50.0));
+
+        assert_eq!(node_pos(&world, entity), Vec2::new(100.0, 50.0));
+        assert!(world.satisfies::<&Pinned>(entity).unwrap());
+
+        release_node(&mut world, dragged_node);
+
+        assert!(!world.satisfies::<&Pinned>(entity).unwrap());
+
+        grab_node(&mut world, &mut view, entity, Vec2::new(100.0, 50.0), 0.2);
+        release_node(&mut world, view.dragged_node.unwrap());
+
+        assert!(world.satisfies::<&Pinned>(entity).unwrap());
+
+        grab_node(&mut world, &mut view, entity, Vec2::new(100.0, 50.0), 1.0);
+        release_node(&mut world, view.dragged_node.unwrap());
+
+        assert!(world.satisfies::<&Pinned>(entity).unwrap());
+    }
+
+    #[test]
+    fn should_trace_self_loops_outside_the_node() {
+        let node_pos = Vec2 { x: 10.0, y: 10.0 };
+
+        for lane in [0.0, 1.0, 2.0] {
+            let path = calculate_self_loop_path(node_pos, NODE_SIZE, lane);
+            let (_, arrow_tip, _) = calculate_self_loop_positions(node_pos, NODE_SIZE, lane);
+
+            assert!((path[0].distance(node_pos) - NODE_SIZE).abs() < 1e-3);
+            assert!(path.last().unwrap().distance(arrow_tip) < 1e-3);
+            assert!(path[1..path.len() - 1]
+                .iter()
+                .all(|pos| pos.distance(node_pos) > NODE_SIZE));
+        }
+    }
+
+    #[test]
+    fn should_split_paths_into_dashes() {
+        let path = [Vec2::ZERO, Vec2::new(5.0, 0.0), Vec2::new(20.0, 0.0)];
+
+        let dashes = calculate_dashes(&path, 4.0, 2.0);
+
+        assert_eq!(
+            dashes,
+            vec![
+                (Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0)),
+                (Vec2::new(6.0, 0.0), Vec2::new(10.0, 0.0)),
+                (Vec2::new(12.0, 0.0), Vec2::new(16.0, 0.0)),
+                (Vec2::new(18.0, 0.0), Vec2::new(20.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_scale_edge_thickness_with_weight() {
+        let style = EdgeStyle::default();
+
+        assert_eq!(edge_thickness(&style, 1.0), 3.0);
+        assert_eq!(edge_thickness(&style, 2.0), 6.0);
+        assert_eq!(edge_thickness(&style, 0.1), 1.0);
+        assert_eq!(edge_thickness(&style, 100.0), 12.0);
+
+        let wide_style = EdgeStyle {
+            width: 20.0,
+            ..EdgeStyle::default()
+        };
+
+        assert_eq!(edge_thickness(&wide_style, 1.0), 20.0);
     }
 }
@@ -0,0 +1,82 @@
+use macroquad::color::{Color, BLACK, RED, WHITE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeShape {
+    #[default]
+    Circle,
+    Square,
+    Diamond,
+    RoundedRect,
+}
+
+/// How a node is drawn. The radius is also used by the physics to keep nodes from overlapping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeStyle {
+    pub fill: Color,
+    pub stroke: Color,
+    /// Width of the outline; zero draws no outline.
+    pub stroke_width: f32,
+    /// Distance from the centre of the node to its edge. For squares this is half the side.
+    pub radius: f32,
+    pub shape: NodeShape,
+    pub font_size: u16,
+    pub label_color: Color,
+}
+
+impl Default for NodeStyle {
+    fn default() -> Self {
+        Self {
+            fill: BLACK,
+            stroke: WHITE,
+            stroke_width: 0.0,
+            radius: 15.0,
+            shape: NodeShape::Circle,
+            font_size: 20,
+            label_color: WHITE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinePattern {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrowHead {
+    None,
+    #[default]
+    Open,
+    Filled,
+    Diamond,
+    Circle,
+}
+
+/// How an edge is drawn. Edges without a style of their own use the graph's default edge style.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeStyle {
+    pub color: Color,
+    /// Line width of an edge with weight 1. Heavier edges are drawn proportionally thicker.
+    pub width: f32,
+    pub pattern: LinePattern,
+    /// Drawn where a directed edge ends.
+    pub arrowhead: ArrowHead,
+    /// Also draws the arrowhead where the edge starts. Undirected edges only get arrowheads when
+    /// this is set.
+    pub both_ends: bool,
+}
+
+impl Default for EdgeStyle {
+    fn default() -> Self {
+        Self {
+            color: RED,
+            width: 3.0,
+            pattern: LinePattern::Solid,
+            arrowhead: ArrowHead::Open,
+            both_ends: false,
+        }
+    }
+}
@@ -0,0 +1,901 @@
+use crate::graph::{
+    spawn_graph, spawn_node, Cluster, Edge, Frozen, Graph, Metadata, NodeId, Pinned, Position,
+    Size, Velocity,
+};
+use crate::physics::{edges_by_entity, physics_update, PhysicsConfig};
+use crate::renderer::{self, render, Selected};
+pub use crate::renderer::{ScrollableView, SelectionEvent};
+use crate::style::{EdgeStyle, NodeStyle};
+use hecs::{Entity, World};
+use macroquad::math::{Rect, Vec2};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f32::consts::PI;
+
+// Simulation steps run per second, which is one step per frame at 60 frames per second.
+const STEPS_PER_SECOND: f32 = 60.0;
+// The most steps a single update catches up on, so one slow frame does not slow the next ones.
+const MAX_STEPS_PER_UPDATE: f32 = 4.0;
+// How many edges away from a change to the graph nodes still move when a settled simulation is
+// restarted by the change. Everything further away stays where it is.
+const REHEAT_DEPTH: usize = 2;
+// Turning by the golden angle between new nodes spreads them evenly around their neighbours.
+const GOLDEN_ANGLE: f32 = PI * 0.763_932;
+
+type NodeTooltip<N> = Box<dyn Fn(&N) -> String>;
+
+/// Draws a [`Graph`] and lets the user interact with it from within a macroquad loop owned by the
+/// caller, who can draw anything else around it.
+///
+/// ```no_run
+/// use emerge::graph::Graph;
+/// use emerge::view::GraphView;
+/// use macroquad::prelude::*;
+///
+/// # async fn run() {
+/// let mut graph = Graph::new();
+/// graph.add_node("A");
+/// graph.add_node("B");
+/// graph.add_directed_edge("A", "B");
+///
+/// let mut graph_view = GraphView::new(graph);
+///
+/// loop {
+///     clear_background(BLACK);
+///
+///     graph_view.handle_input();
+///     graph_view.update(get_frame_time());
+///     graph_view.draw(Rect::new(200.0, 0.0, screen_width() - 200.0, screen_height()));
+///
+///     draw_text("My HUD", 20.0, 40.0, 30.0, WHITE);
+///
+///     next_frame().await
+/// }
+/// # }
+/// ```
+pub struct GraphView<N = (), E = ()> {
+    graph: Graph<N, E>,
+    world: World,
+    view_entity: Entity,
+    config: PhysicsConfig,
+    is_settled: bool,
+    // Steps owed to the simulation from the time passed, carried over between updates.
+    pending_steps: f32,
+    is_fitted: bool,
+    // Nodes added while the graph is shown that are not yet connected to any node already placed,
+    // so they are moved next to the first such neighbour they get.
+    unplaced_node_ids: HashSet<usize>,
+    // Describes the data of the hovered node in its tooltip.
+    node_tooltip: Option<NodeTooltip<N>>,
+}
+
+/// A change to a graph being shown, for code that cannot reach its [`GraphView`], such as a
+/// producer on another thread. Nodes are referred to by label, and updates naming a node that does
+/// not exist are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphUpdate {
+    /// Adds a node unless one already has this label.
+    AddNode {
+        label: String,
+    },
+    RemoveNode {
+        label: String,
+    },
+    AddEdge {
+        from: String,
+        to: String,
+        is_directed: bool,
+        weight: f32,
+    },
+    /// Removes the edges [`Graph::remove_edge`] would.
+    RemoveEdge {
+        from: String,
+        to: String,
+    },
+    SetNodeStyle {
+        label: String,
+        style: NodeStyle,
+    },
+    /// Restyles every edge between the two nodes, or makes them follow the graph's default edge
+    /// style again when `style` is `None`.
+    SetEdgeStyle {
+        from: String,
+        to: String,
+        style: Option<EdgeStyle>,
+    },
+    SetNodeMetadata {
+        label: String,
+        key: String,
+        value: String,
+    },
+}
+
+impl GraphUpdate {
+    fn apply<N: Default, E: Default>(self, graph: &mut Graph<N, E>) {
+        match self {
+            GraphUpdate::AddNode { label } => {
+                graph.add_node(&label);
+            }
+            GraphUpdate::RemoveNode { label } => {
+                graph.remove_node(&label);
+            }
+            GraphUpdate::AddEdge {
+                from,
+                to,
+                is_directed: true,
+                weight,
+            } => {
+                graph.add_weighted_edge(&from, &to, weight);
+            }
+            GraphUpdate::AddEdge {
+                from,
+                to,
+                is_directed: false,
+                weight,
+            } => {
+                graph.add_weighted_undirected_edge(&from, &to, weight);
+            }
+            GraphUpdate::RemoveEdge { from, to } => {
+                graph.remove_edge(&from, &to);
+            }
+            GraphUpdate::SetNodeStyle { label, style } => {
+                graph.set_node_style(&label, style);
+            }
+            GraphUpdate::SetEdgeStyle { from, to, style } => {
+                for edge in graph.edges_between(&from, &to) {
+                    graph.set_edge_style(edge, style);
+                }
+            }
+            GraphUpdate::SetNodeMetadata { label, key, value } => {
+                graph.set_node_metadata(&label, &key, &value);
+            }
+        }
+    }
+}
+
+impl<N, E> GraphView<N, E> {
+    /// The view is fitted to the graph the first time it is drawn, so this can be called before
+    /// the window size is known.
+    pub fn new(graph: Graph<N, E>) -> Self {
+        let mut world = spawn_graph(&graph, Vec2::ZERO);
+        let view_entity = world.spawn((ScrollableView::new(),));
+
+        Self {
+            graph,
+            world,
+            view_entity,
+            config: PhysicsConfig::default(),
+            is_settled: false,
+            pending_steps: 0.0,
+            is_fitted: false,
+            unplaced_node_ids: HashSet::new(),
+            node_tooltip: None,
+        }
+    }
+
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    pub fn with_config(mut self, config: PhysicsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &PhysicsConfig {
+        &self.config
+    }
+
+    /// Shows the text `tooltip` returns for a node's data in the tooltip of the hovered node,
+    /// below its label and metadata, one line per line of text.
+    pub fn with_node_tooltip(mut self, tooltip: impl Fn(&N) -> String + 'static) -> Self {
+        self.node_tooltip = Some(Box::new(tooltip));
+        self
+    }
+
+    /// Changes the physics while the graph is shown, restarting the simulation if it had settled.
+    pub fn set_config(&mut self, config: PhysicsConfig) {
+        self.config = config;
+        self.is_settled = false;
+        self.thaw();
+    }
+
+    /// Changes the graph while it is shown, for example to add or remove nodes and edges or to
+    /// restyle them. New nodes appear next to the nodes they are connected to. If the layout had
+    /// settled, only the part of the graph around the change moves again.
+    pub fn edit<R>(&mut self, edit: impl FnOnce(&mut Graph<N, E>) -> R) -> R {
+        let pinned_node_ids: HashSet<usize> = self
+            .graph
+            .nodes
+            .values()
+            .filter(|node| node.is_pinned)
+            .map(|node| node.id)
+            .collect();
+
+        let result = edit(&mut self.graph);
+
+        let changed_node_ids = self.sync_world(&pinned_node_ids);
+        self.place_new_nodes();
+        self.reheat(&changed_node_ids);
+
+        result
+    }
+
+    // Brings the world up to date with the graph, returning the ids of the nodes whose
+    // connections, size or pinning changed, including nodes that were added or removed.
+    fn sync_world(&mut self, pinned_node_ids: &HashSet<usize>) -> HashSet<usize> {
+        let mut changed_node_ids = HashSet::new();
+        let entities_by_id: HashMap<usize, Entity> = self
+            .world
+            .query::<&usize>()
+            .iter()
+            .map(|(entity, &node_id)| (node_id, entity))
+            .collect();
+
+        for (&node_id, &entity) in &entities_by_id {
+            if !self.graph.nodes.contains_key(&node_id) {
+                renderer::forget_entity(&mut self.world, entity);
+                self.world.despawn(entity).unwrap();
+                self.unplaced_node_ids.remove(&node_id);
+                changed_node_ids.insert(node_id);
+            }
+        }
+
+        // Edges are respawned from the graph rather than matched up, as adding or removing one
+        // edge can move every other edge between the same nodes into a different lane.
+        let old_edges = edges_by_entity(&self.world);
+        let new_edges = self.graph.get_all_edges();
+        let old_edge_counts = count_edges(old_edges.iter().map(|(_, edge)| edge));
+        let new_edge_counts = count_edges(&new_edges);
+
+        for (edge_key, count) in old_edge_counts.iter().chain(&new_edge_counts) {
+            if old_edge_counts.get(edge_key) != Some(count)
+                || new_edge_counts.get(edge_key) != Some(count)
+            {
+                changed_node_ids.insert(edge_key.0);
+                changed_node_ids.insert(edge_key.1);
+            }
+        }
+
+        for (entity, _) in old_edges {
+            renderer::forget_entity(&mut self.world, entity);
+            self.world.despawn(entity).unwrap();
+        }
+
+        for edge in new_edges {
+            self.world.spawn((edge,));
+        }
+
+        let spawn_position = renderer::graph_centroid(&self.world).unwrap_or(Vec2::ZERO);
+
+        for node in self.graph.nodes.values() {
+            let Some(&entity) = entities_by_id.get(&node.id) else {
+                let position =
+                    spawn_position + node_offset(node.id, self.config.spring_resting_length);
+
+                spawn_node(&mut self.world, node, position);
+                self.unplaced_node_ids.insert(node.id);
+                changed_node_ids.insert(node.id);
+                continue;
+            };
+
+            let (label, style, size, Metadata(metadata)) = self
+                .world
+                .query_one_mut::<(&mut String, &mut NodeStyle, &mut Size, &mut Metadata)>(entity)
+                .unwrap();
+
+            if size.radius != node.style.radius {
+                changed_node_ids.insert(node.id);
+            }
+
+            label.clone_from(&node.label);
+            *style = node.style;
+            size.radius = node.style.radius;
+            metadata.clone_from(&node.metadata.0);
+
+            match &node.cluster {
+                Some(cluster) => self
+                    .world
+                    .insert_one(entity, Cluster(cluster.clone()))
+                    .unwrap(),
+                None => {
+                    let _ = self.world.remove_one::<Cluster>(entity);
+                }
+            }
+
+            // Pinning is only updated when the edit changes it, so a node being dragged stays held
+            // under the cursor.
+            if node.is_pinned != pinned_node_ids.contains(&node.id) {
+                if node.is_pinned {
+                    self.world.insert_one(entity, Pinned).unwrap();
+                } else {
+                    let _ = self.world.remove_one::<Pinned>(entity);
+                }
+
+                changed_node_ids.insert(node.id);
+            }
+        }
+
+        changed_node_ids
+    }
+
+    // Moves new nodes next to the neighbours they have among the nodes already placed, placing
+    // chains of new nodes outwards from the existing graph one link at a time.
+    fn place_new_nodes(&mut self) {
+        let neighbours = neighbours_by_id(&self.graph);
+        let mut positions: HashMap<usize, (Entity, Vec2)> = self
+            .world
+            .query::<(&usize, &Position)>()
+            .iter()
+            .map(|(entity, (&node_id, position))| {
+                (node_id, (entity, Vec2::new(position.x, position.y)))
+            })
+            .collect();
+
+        loop {
+            let placements: Vec<(usize, Vec2)> = self
+                .unplaced_node_ids
+                .iter()
+                .filter_map(|node_id| {
+                    let placed_neighbours: Vec<Vec2> = neighbours
+                        .get(node_id)?
+                        .iter()
+                        .filter(|neighbour_id| !self.unplaced_node_ids.contains(neighbour_id))
+                        .filter_map(|neighbour_id| positions.get(neighbour_id))
+                        .map(|&(_, position)| position)
+                        .collect();
+
+                    if placed_neighbours.is_empty() {
+                        return None;
+                    }
+
+                    let anchor =
+                        placed_neighbours.iter().sum::<Vec2>() / placed_neighbours.len() as f32;
+
+                    Some((
+                        *node_id,
+                        anchor + node_offset(*node_id, self.config.spring_resting_length),
+                    ))
+                })
+                .collect();
+
+            if placements.is_empty() {
+                break;
+            }
+
+            for (node_id, placement) in placements {
+                let Some((entity, position)) = positions.get_mut(&node_id) else {
+                    continue;
+                };
+                let (stored_position, velocity) = self
+                    .world
+                    .query_one_mut::<(&mut Position, &mut Velocity)>(*entity)
+                    .unwrap();
+
+                *stored_position = Position {
+                    x: placement.x,
+                    y: placement.y,
+                };
+                *velocity = Velocity { x: 0.0, y: 0.0 };
+                *position = placement;
+                self.unplaced_node_ids.remove(&node_id);
+            }
+        }
+    }
+
+    // Restarts the simulation after a change to the graph. A settled layout only moves the nodes
+    // near the change, while a layout still settling keeps moving as it was.
+    fn reheat(&mut self, changed_node_ids: &HashSet<usize>) {
+        if changed_node_ids.is_empty() {
+            return;
+        }
+
+        let has_frozen_nodes = self.world.query::<&Frozen>().iter().next().is_some();
+
+        if self.is_settled {
+            let node_entities: Vec<Entity> = self
+                .world
+                .query::<&usize>()
+                .iter()
+                .map(|(entity, _)| entity)
+                .collect();
+
+            for entity in node_entities {
+                self.world.insert_one(entity, Frozen).unwrap();
+            }
+
+            self.is_settled = false;
+        } else if !has_frozen_nodes {
+            return;
+        }
+
+        let nearby_node_ids = nodes_within(&self.graph, changed_node_ids, REHEAT_DEPTH);
+        let nearby_entities: Vec<Entity> = self
+            .world
+            .query::<&usize>()
+            .iter()
+            .filter(|(_, node_id)| nearby_node_ids.contains(node_id))
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in nearby_entities {
+            let _ = self.world.remove_one::<Frozen>(entity);
+        }
+    }
+
+    fn thaw(&mut self) {
+        let frozen_entities: Vec<Entity> = self
+            .world
+            .query::<&Frozen>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in frozen_entities {
+            let _ = self.world.remove_one::<Frozen>(entity);
+        }
+    }
+
+    /// The scroll position, zoom and interaction options of the view.
+    pub fn view_mut(&mut self) -> &mut ScrollableView {
+        self.world
+            .query_one_mut::<&mut ScrollableView>(self.view_entity)
+            .expect("No scrollable view found")
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.is_settled
+    }
+
+    /// Advances the simulation by `delta_time` seconds until it settles.
+    pub fn update(&mut self, delta_time: f32) {
+        if self.is_settled {
+            self.pending_steps = 0.0;
+            return;
+        }
+
+        self.pending_steps =
+            (self.pending_steps + delta_time * STEPS_PER_SECOND).min(MAX_STEPS_PER_UPDATE);
+
+        while self.pending_steps >= 1.0 && !self.is_settled {
+            self.pending_steps -= 1.0;
+            self.is_settled =
+                physics_update(&mut self.world, &self.config).is_settled(&self.config);
+        }
+
+        if self.is_settled {
+            self.thaw();
+        }
+    }
+
+    /// Pans, zooms, drags and selects from this frame's input. Input is taken from where the view
+    /// was last drawn, or the whole screen before it has been drawn.
+    pub fn handle_input(&mut self) {
+        if renderer::view_port_update(&mut self.world, self.is_settled) {
+            self.is_settled = false;
+            self.thaw();
+        }
+
+        self.sync_pins_to_graph();
+    }
+
+    // Copies the pins the user toggled by double-clicking into the graph, so the graph reports them
+    // and an edit can undo them.
+    fn sync_pins_to_graph(&mut self) {
+        let pinned_node_ids = renderer::pinned_node_ids(&self.world);
+
+        for node in self.graph.nodes.values_mut() {
+            node.is_pinned = pinned_node_ids.contains(&node.id);
+        }
+    }
+
+    /// Zooms and scrolls so every node fits where the view was last drawn, or the whole screen
+    /// before it has been drawn.
+    pub fn fit_to_view(&mut self) {
+        let mut view = self.view_mut().clone();
+        let screen_size = view.viewport().size();
+
+        view.fit_to_view(&self.world, screen_size);
+        *self.view_mut() = view;
+        self.is_fitted = true;
+    }
+
+    /// Draws the graph into `rect`, in screen pixels, without drawing outside it.
+    pub fn draw(&mut self, rect: Rect) {
+        let mut view = self.view_mut().clone();
+
+        view.set_viewport(rect);
+
+        if !self.is_fitted && !self.graph.nodes.is_empty() {
+            view.fit_to_view(&self.world, rect.size());
+            self.is_fitted = true;
+        }
+
+        *self.view_mut() = view;
+
+        let data_lines = self.hovered_node_tooltip();
+
+        render(&mut self.world, &data_lines);
+    }
+
+    fn hovered_node_tooltip(&self) -> Vec<String> {
+        let Some(node_tooltip) = &self.node_tooltip else {
+            return Vec::new();
+        };
+
+        renderer::hovered_node_id(&self.world)
+            .and_then(|node_id| self.graph.nodes.get(&node_id))
+            .map(|node| {
+                node_tooltip(&node.data)
+                    .lines()
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The ids of the nodes the user has selected.
+    pub fn selected_nodes(&self) -> Vec<NodeId> {
+        let mut node_ids: Vec<NodeId> = self
+            .world
+            .query::<(&usize, &Selected)>()
+            .iter()
+            .map(|(_, (&node_id, _))| NodeId(node_id))
+            .collect();
+
+        node_ids.sort();
+        node_ids
+    }
+
+    /// Takes the changes the user has made to the selection since the last call, oldest first. Only
+    /// the most recent few hundred are kept between calls, so a view that is never drained does not
+    /// keep growing.
+    pub fn drain_selection_events(&mut self) -> Vec<SelectionEvent> {
+        renderer::drain_selection_events(&mut self.world)
+    }
+}
+
+impl<N: Default, E: Default> GraphView<N, E> {
+    /// Applies updates in order as a single change to the graph, for example everything received
+    /// on a channel since the last frame.
+    pub fn apply_updates(&mut self, updates: impl IntoIterator<Item = GraphUpdate>) {
+        let updates: Vec<GraphUpdate> = updates.into_iter().collect();
+
+        if updates.is_empty() {
+            return;
+        }
+
+        self.edit(|graph| {
+            for update in updates {
+                update.apply(graph);
+            }
+        });
+    }
+}
+
+// How many edges run between each pair of nodes in each direction, with each weight.
+fn count_edges<'a>(
+    edges: impl IntoIterator<Item = &'a Edge>,
+) -> HashMap<(usize, usize, bool, u32), usize> {
+    let mut counts = HashMap::new();
+
+    for edge in edges {
+        let edge_key = (
+            edge.source_node_id,
+            edge.destination_node_id,
+            edge.is_directed,
+            edge.weight.to_bits(),
+        );
+
+        *counts.entry(edge_key).or_default() += 1;
+    }
+
+    counts
+}
+
+fn neighbours_by_id<N, E>(graph: &Graph<N, E>) -> HashMap<usize, Vec<usize>> {
+    let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for edge in graph.edges.values() {
+        neighbours
+            .entry(edge.source_node_id)
+            .or_default()
+            .push(edge.destination_node_id);
+        neighbours
+            .entry(edge.destination_node_id)
+            .or_default()
+            .push(edge.source_node_id);
+    }
+
+    neighbours
+}
+
+// The given nodes and every node at most `depth` edges away from them, whichever way the edges
+// point.
+fn nodes_within<N, E>(
+    graph: &Graph<N, E>,
+    node_ids: &HashSet<usize>,
+    depth: usize,
+) -> HashSet<usize> {
+    let neighbours = neighbours_by_id(graph);
+    let mut reached = node_ids.clone();
+    let mut queue: VecDeque<(usize, usize)> =
+        node_ids.iter().map(|&node_id| (node_id, 0)).collect();
+
+    while let Some((node_id, distance)) = queue.pop_front() {
+        if distance == depth {
+            continue;
+        }
+
+        for &neighbour_id in neighbours.get(&node_id).into_iter().flatten() {
+            if reached.insert(neighbour_id) {
+                queue.push_back((neighbour_id, distance + 1));
+            }
+        }
+    }
+
+    reached
+}
+
+// Where a new node goes relative to the nodes it is placed next to.
+fn node_offset(node_id: usize, distance: f32) -> Vec2 {
+    Vec2::from_angle(GOLDEN_ANGLE * node_id as f32) * distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Position;
+
+    fn node_positions(graph_view: &GraphView) -> Vec<(usize, Vec2)> {
+        let mut positions: Vec<_> = graph_view
+            .world
+            .query::<(&usize, &Position)>()
+            .iter()
+            .map(|(_, (&node_id, position))| (node_id, Vec2::new(position.x, position.y)))
+            .collect();
+
+        positions.sort_by_key(|&(node_id, _)| node_id);
+        positions
+    }
+
+    #[test]
+    fn should_step_the_simulation_with_the_time_passed() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_node("C");
+        graph.add_directed_edge("A", "B");
+        graph.add_directed_edge("B", "C");
+
+        let mut graph_view = GraphView::new(graph);
+        let initial_positions = node_positions(&graph_view);
+
+        graph_view.update(0.5 / STEPS_PER_SECOND);
+
+        assert_eq!(node_positions(&graph_view), initial_positions);
+
+        graph_view.update(0.6 / STEPS_PER_SECOND);
+
+        assert_ne!(node_positions(&graph_view), initial_positions);
+    }
+
+    #[test]
+    fn should_settle_and_restart_when_the_config_changes() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_undirected_edge("A", "B");
+
+        let mut graph_view = GraphView::new(graph);
+
+        for _ in 0..10_000 {
+            graph_view.update(1.0 / STEPS_PER_SECOND);
+        }
+
+        assert!(graph_view.is_settled());
+
+        graph_view.set_config(PhysicsConfig {
+            spring_resting_length: 300.0,
+            ..PhysicsConfig::default()
+        });
+
+        assert!(!graph_view.is_settled());
+    }
+
+    fn settle(graph_view: &mut GraphView) {
+        for _ in 0..10_000 {
+            if graph_view.is_settled() {
+                return;
+            }
+
+            graph_view.update(1.0 / STEPS_PER_SECOND);
+        }
+
+        panic!("The layout did not settle");
+    }
+
+    fn position(graph_view: &GraphView, id: NodeId) -> Vec2 {
+        node_positions(graph_view)
+            .into_iter()
+            .find(|&(node_id, _)| node_id == id.0)
+            .map(|(_, position)| position)
+            .unwrap()
+    }
+
+    #[test]
+    fn should_spawn_new_nodes_next_to_their_neighbours() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_undirected_edge("A", "B");
+
+        let mut graph_view = GraphView::new(graph);
+        settle(&mut graph_view);
+
+        let a = graph_view.graph().node_id("A").unwrap();
+        let c = graph_view.edit(|graph| {
+            let c = graph.add_node("C");
+            graph.add_undirected_edge("A", c);
+            c
+        });
+
+        let distance = position(&graph_view, c).distance(position(&graph_view, a));
+
+        assert!((distance - PhysicsConfig::default().spring_resting_length).abs() < 1e-3);
+
+        // A node added on its own is placed once it is first connected.
+        let d = graph_view.edit(|graph| graph.add_node("D"));
+        graph_view.edit(|graph| graph.add_undirected_edge(c, d));
+
+        let distance = position(&graph_view, d).distance(position(&graph_view, c));
+
+        assert!((distance - PhysicsConfig::default().spring_resting_length).abs() < 1e-3);
+        assert!(graph_view.unplaced_node_ids.is_empty());
+    }
+
+    #[test]
+    fn should_only_move_nodes_near_a_change_once_settled() {
+        let mut graph = Graph::new();
+        let node_ids: Vec<NodeId> = (0..8)
+            .map(|index| graph.add_node(&index.to_string()))
+            .collect();
+
+        for pair in node_ids.windows(2) {
+            graph.add_undirected_edge(pair[0], pair[1]);
+        }
+
+        let mut graph_view = GraphView::new(graph);
+        settle(&mut graph_view);
+
+        let far_end = position(&graph_view, node_ids[7]);
+
+        graph_view.edit(|graph| {
+            let new_node = graph.add_node("new");
+            graph.add_undirected_edge(new_node, node_ids[0]);
+        });
+
+        assert!(!graph_view.is_settled());
+
+        for _ in 0..10 {
+            graph_view.update(1.0 / STEPS_PER_SECOND);
+        }
+
+        assert_eq!(position(&graph_view, node_ids[7]), far_end);
+
+        settle(&mut graph_view);
+
+        assert!(graph_view.world.query::<&Frozen>().iter().next().is_none());
+    }
+
+    #[test]
+    fn should_remove_and_restyle_nodes_while_shown() {
+        let mut graph = Graph::new();
+
+        graph.add_node("A");
+        graph.add_node("B");
+        graph.add_directed_edge("A", "B");
+
+        let mut graph_view = GraphView::new(graph);
+
+        graph_view.edit(|graph| {
+            graph.node_style_mut("A").unwrap().radius = 40.0;
+            graph.remove_node("B");
+        });
+
+        let world = &graph_view.world;
+
+        assert_eq!(world.query::<&usize>().iter().count(), 1);
+        assert_eq!(world.query::<&Edge>().iter().count(), 0);
+        assert!(world
+            .query::<(&Size, &NodeStyle)>()
+            .iter()
+            .all(|(_, (size, style))| size.radius == 40.0 && style.radius == 40.0));
+    }
+
+    #[test]
+    fn should_let_the_graph_unpin_nodes_the_user_pinned() {
+        let mut graph = Graph::new();
+
+        let a = graph.add_node("A");
+        let mut graph_view = GraphView::new(graph);
+        let entity = graph_view
+            .world
+            .query::<&usize>()
+            .iter()
+            .find(|&(_, &node_id)| node_id == a.0)
+            .map(|(entity, _)| entity)
+            .unwrap();
+
+        graph_view.world.insert_one(entity, Pinned).unwrap();
+        graph_view.sync_pins_to_graph();
+
+        assert!(graph_view.graph().is_pinned(a));
+
+        graph_view.edit(|graph| graph.set_pinned(a, false));
+
+        assert!(!graph_view.graph().is_pinned(a));
+        assert!(graph_view.world.get::<&Pinned>(entity).is_err());
+    }
+
+    #[test]
+    fn should_apply_updates_sent_from_another_thread() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut graph_view = GraphView::new(Graph::new());
+
+        std::thread::spawn(move || {
+            for label in ["A", "B", "C"] {
+                sender
+                    .send(GraphUpdate::AddNode {
+                        label: label.to_string(),
+                    })
+                    .unwrap();
+            }
+
+            sender
+                .send(GraphUpdate::AddEdge {
+                    from: "A".to_string(),
+                    to: "B".to_string(),
+                    is_directed: true,
+                    weight: 2.0,
+                })
+                .unwrap();
+            sender
+                .send(GraphUpdate::AddEdge {
+                    from: "B".to_string(),
+                    to: "Missing".to_string(),
+                    is_directed: false,
+                    weight: 1.0,
+                })
+                .unwrap();
+            sender
+                .send(GraphUpdate::RemoveNode {
+                    label: "C".to_string(),
+                })
+                .unwrap();
+            sender
+                .send(GraphUpdate::SetNodeMetadata {
+                    label: "A".to_string(),
+                    key: "kind".to_string(),
+                    value: "source".to_string(),
+                })
+                .unwrap();
+        })
+        .join()
+        .unwrap();
+
+        graph_view.apply_updates(receiver.try_iter());
+
+        let graph = graph_view.graph();
+        let a = graph.node_id("A").unwrap();
+        let b = graph.node_id("B").unwrap();
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges_between(a, b).len(), 1);
+        assert_eq!(graph.node_metadata(a, "kind"), Some("source"));
+        assert_eq!(graph_view.world.query::<&usize>().iter().count(), 2);
+        assert_eq!(graph_view.world.query::<&Edge>().iter().count(), 1);
+    }
+}